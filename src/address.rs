@@ -0,0 +1,559 @@
+//! Bech32 TOS addresses: `tos1…` on mainnet, `tst1…` on testnet (see [`Network`]).
+//!
+//! The payload is the 32-byte compressed Ristretto public key followed by one
+//! address type byte, encoded with the original bech32 checksum (not bech32m).
+//! Integrated addresses (type `1`) append payment data, such as a payment ID
+//! or memo, after the type byte as a serialized TOS [`DataElement`].
+
+use bech32::{FromBase32, ToBase32, Variant};
+use curve25519_dalek::ristretto::CompressedRistretto;
+
+use crate::{Network, WalletError};
+
+const PUBLIC_KEY_SIZE: usize = 32;
+const HASH_SIZE: usize = 32;
+/// Largest serialized payment data accepted in an integrated address, in
+/// bytes; the node's `EXTRA_DATA_LIMIT_SIZE`.
+pub const MAX_INTEGRATED_DATA_SIZE: usize = 1024;
+/// Deepest nesting of arrays and maps accepted in payment data.
+const MAX_DATA_DEPTH: usize = 16;
+
+/// A single typed value inside a [`DataElement`].
+///
+/// Serialized as a tag byte followed by the value; integers are big-endian
+/// and strings carry a one-byte length.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DataValue {
+    /// Tag `0`, one byte `0` or `1`.
+    Bool(bool),
+    /// Tag `1`, at most 255 bytes of UTF-8.
+    String(String),
+    /// Tag `2`.
+    U8(u8),
+    /// Tag `3`.
+    U16(u16),
+    /// Tag `4`.
+    U32(u32),
+    /// Tag `5`.
+    U64(u64),
+    /// Tag `6`.
+    U128(u128),
+    /// Tag `7`, a 32-byte hash.
+    Hash([u8; HASH_SIZE]),
+}
+
+impl DataValue {
+    fn write(&self, output: &mut Vec<u8>) -> Result<(), WalletError> {
+        match self {
+            Self::Bool(value) => output.extend([0, u8::from(*value)]),
+            Self::String(value) => {
+                let length = u8::try_from(value.len()).map_err(|_| {
+                    WalletError::InvalidAddress(format!("data string is {} bytes, at most 255 allowed", value.len()))
+                })?;
+                output.extend([1, length]);
+                output.extend_from_slice(value.as_bytes());
+            }
+            Self::U8(value) => output.extend([2, *value]),
+            Self::U16(value) => {
+                output.push(3);
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::U32(value) => {
+                output.push(4);
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::U64(value) => {
+                output.push(5);
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::U128(value) => {
+                output.push(6);
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::Hash(value) => {
+                output.push(7);
+                output.extend_from_slice(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn read(reader: &mut DataReader) -> Result<Self, WalletError> {
+        Ok(match reader.byte()? {
+            0 => match reader.byte()? {
+                0 => Self::Bool(false),
+                1 => Self::Bool(true),
+                other => return Err(WalletError::InvalidAddress(format!("invalid data bool {other}"))),
+            },
+            1 => {
+                let length = reader.byte()?;
+                let bytes = reader.take(usize::from(length))?;
+                Self::String(
+                    String::from_utf8(bytes.to_vec())
+                        .map_err(|_| WalletError::InvalidAddress("data string is not UTF-8".to_string()))?,
+                )
+            }
+            2 => Self::U8(reader.byte()?),
+            3 => Self::U16(u16::from_be_bytes(reader.array()?)),
+            4 => Self::U32(u32::from_be_bytes(reader.array()?)),
+            5 => Self::U64(u64::from_be_bytes(reader.array()?)),
+            6 => Self::U128(u128::from_be_bytes(reader.array()?)),
+            7 => Self::Hash(reader.array()?),
+            other => return Err(WalletError::InvalidAddress(format!("unknown data value type {other}"))),
+        })
+    }
+}
+
+/// Payment data of an integrated address, as the TOS wallet and node
+/// serialize it.
+///
+/// Tag `0` is a single value, tag `1` an array and tag `2` a map of fields;
+/// arrays and maps carry a one-byte length.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DataElement {
+    /// Tag `0`.
+    Value(DataValue),
+    /// Tag `1`, at most 255 elements.
+    Array(Vec<DataElement>),
+    /// Tag `2`, at most 255 fields with distinct keys, kept in order.
+    Fields(Vec<(DataValue, DataElement)>),
+}
+
+impl DataElement {
+    /// Serialized form, as carried after the address type byte.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WalletError> {
+        let mut output = Vec::new();
+        self.write(&mut output, 0)?;
+        Ok(output)
+    }
+
+    /// Parses a serialized element; trailing bytes are rejected.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WalletError> {
+        let mut reader = DataReader(bytes);
+        let element = Self::read(&mut reader, 0)?;
+        if !reader.0.is_empty() {
+            return Err(WalletError::InvalidAddress(format!(
+                "{} unexpected bytes after the integrated data",
+                reader.0.len()
+            )));
+        }
+        Ok(element)
+    }
+
+    fn write(&self, output: &mut Vec<u8>, depth: usize) -> Result<(), WalletError> {
+        check_depth(depth)?;
+        match self {
+            Self::Value(value) => {
+                output.push(0);
+                value.write(output)?;
+            }
+            Self::Array(elements) => {
+                output.extend([1, collection_size(elements.len())?]);
+                for element in elements {
+                    element.write(output, depth + 1)?;
+                }
+            }
+            Self::Fields(fields) => {
+                output.extend([2, collection_size(fields.len())?]);
+                for (index, (key, element)) in fields.iter().enumerate() {
+                    if fields[..index].iter().any(|(other, _)| other == key) {
+                        return Err(WalletError::InvalidAddress(format!("duplicate data field {key:?}")));
+                    }
+                    key.write(output)?;
+                    element.write(output, depth + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read(reader: &mut DataReader, depth: usize) -> Result<Self, WalletError> {
+        check_depth(depth)?;
+        Ok(match reader.byte()? {
+            0 => Self::Value(DataValue::read(reader)?),
+            1 => {
+                let count = reader.byte()?;
+                Self::Array((0..count).map(|_| Self::read(reader, depth + 1)).collect::<Result<_, _>>()?)
+            }
+            2 => {
+                let count = reader.byte()?;
+                let mut fields: Vec<(DataValue, DataElement)> = Vec::with_capacity(usize::from(count));
+                for _ in 0..count {
+                    let key = DataValue::read(reader)?;
+                    if fields.iter().any(|(other, _)| *other == key) {
+                        return Err(WalletError::InvalidAddress(format!("duplicate data field {key:?}")));
+                    }
+                    fields.push((key, Self::read(reader, depth + 1)?));
+                }
+                Self::Fields(fields)
+            }
+            other => return Err(WalletError::InvalidAddress(format!("unknown data element type {other}"))),
+        })
+    }
+}
+
+fn check_depth(depth: usize) -> Result<(), WalletError> {
+    if depth > MAX_DATA_DEPTH {
+        return Err(WalletError::InvalidAddress(format!(
+            "integrated data is nested more than {MAX_DATA_DEPTH} levels deep"
+        )));
+    }
+    Ok(())
+}
+
+fn collection_size(length: usize) -> Result<u8, WalletError> {
+    u8::try_from(length)
+        .map_err(|_| WalletError::InvalidAddress(format!("data holds {length} entries, at most 255 allowed")))
+}
+
+/// Cursor over serialized integrated data.
+struct DataReader<'a>(&'a [u8]);
+
+impl<'a> DataReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], WalletError> {
+        if self.0.len() < length {
+            return Err(WalletError::InvalidAddress("integrated data is truncated".to_string()));
+        }
+        let (head, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, WalletError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], WalletError> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+}
+
+/// Kind of address, stored as the byte after the public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    /// A plain address; type byte `0`.
+    Normal,
+    /// An integrated address carrying payment data of at most
+    /// [`MAX_INTEGRATED_DATA_SIZE`] serialized bytes; type byte `1`.
+    Data(DataElement),
+}
+
+impl AddressType {
+    /// Lowercase name, `normal` or `data`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Data(_) => "data",
+        }
+    }
+
+    fn to_byte(&self) -> u8 {
+        match self {
+            Self::Normal => 0,
+            Self::Data(_) => 1,
+        }
+    }
+
+    fn from_payload(byte: u8, data: &[u8]) -> Result<Self, WalletError> {
+        check_data_size(data.len())?;
+        Ok(match byte {
+            0 if data.is_empty() => Self::Normal,
+            0 => {
+                return Err(WalletError::InvalidAddress(format!(
+                    "normal address has {} unexpected trailing bytes",
+                    data.len()
+                )))
+            }
+            1 => Self::Data(DataElement::from_bytes(data)?),
+            other => return Err(WalletError::InvalidAddress(format!("unknown address type {other}"))),
+        })
+    }
+
+    /// Serialized payment data, empty for a normal address.
+    fn data_bytes(&self) -> Result<Vec<u8>, WalletError> {
+        match self {
+            Self::Data(data) => {
+                let bytes = data.to_bytes()?;
+                check_data_size(bytes.len())?;
+                Ok(bytes)
+            }
+            Self::Normal => Ok(Vec::new()),
+        }
+    }
+}
+
+fn check_data_size(size: usize) -> Result<(), WalletError> {
+    if size > MAX_INTEGRATED_DATA_SIZE {
+        return Err(WalletError::InvalidAddress(format!(
+            "integrated address data is {size} bytes, at most {MAX_INTEGRATED_DATA_SIZE} allowed"
+        )));
+    }
+    Ok(())
+}
+
+/// A decoded bech32 TOS address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Address {
+    network: Network,
+    public_key: [u8; PUBLIC_KEY_SIZE],
+    address_type: AddressType,
+    /// `address_type`'s payment data as serialized after the type byte.
+    data_bytes: Vec<u8>,
+}
+
+impl Address {
+    /// Address for a compressed Ristretto public key; fails if integrated
+    /// data does not serialize or is too large.
+    pub fn new(
+        network: Network,
+        public_key: [u8; PUBLIC_KEY_SIZE],
+        address_type: AddressType,
+    ) -> Result<Self, WalletError> {
+        Ok(Self {
+            network,
+            public_key,
+            data_bytes: address_type.data_bytes()?,
+            address_type,
+        })
+    }
+
+    /// Parses and validates a `tos1…` or `tst1…` address.
+    pub fn decode(address: &str) -> Result<Self, WalletError> {
+        let (hrp, data, variant) = bech32::decode(address.trim())
+            .map_err(|err| WalletError::InvalidAddress(err.to_string()))?;
+
+        if variant != Variant::Bech32 {
+            return Err(WalletError::InvalidAddress(
+                "bech32m checksums are not used by TOS addresses".to_string(),
+            ));
+        }
+
+        let network = Network::from_address_hrp(&hrp).ok_or_else(|| {
+            WalletError::InvalidAddress(format!(
+                "prefix {hrp:?} must be \"{}\" or \"{}\"",
+                Network::Mainnet.address_hrp(),
+                Network::Testnet.address_hrp()
+            ))
+        })?;
+
+        let payload = Vec::<u8>::from_base32(&data)
+            .map_err(|err| WalletError::InvalidAddress(err.to_string()))?;
+        if payload.len() <= PUBLIC_KEY_SIZE {
+            return Err(WalletError::InvalidAddress(format!(
+                "payload is {} bytes, expected at least {}",
+                payload.len(),
+                PUBLIC_KEY_SIZE + 1
+            )));
+        }
+
+        let mut public_key = [0u8; PUBLIC_KEY_SIZE];
+        public_key.copy_from_slice(&payload[..PUBLIC_KEY_SIZE]);
+        if CompressedRistretto(public_key).decompress().is_none() {
+            return Err(WalletError::InvalidAddress(
+                "public key is not a valid Ristretto point".to_string(),
+            ));
+        }
+
+        let data_bytes = payload[PUBLIC_KEY_SIZE + 1..].to_vec();
+        Ok(Self {
+            network,
+            public_key,
+            address_type: AddressType::from_payload(payload[PUBLIC_KEY_SIZE], &data_bytes)?,
+            data_bytes,
+        })
+    }
+
+    /// Bech32 string form.
+    pub fn encode(&self) -> String {
+        let mut payload = Vec::with_capacity(PUBLIC_KEY_SIZE + 1 + self.data_bytes.len());
+        payload.extend_from_slice(&self.public_key);
+        payload.push(self.address_type.to_byte());
+        payload.extend_from_slice(&self.data_bytes);
+
+        bech32::encode(self.network.address_hrp(), payload.to_base32(), Variant::Bech32)
+            .expect("TOS HRPs are valid bech32 prefixes")
+    }
+
+    /// Network selected by the prefix.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Compressed Ristretto public key.
+    pub fn public_key(&self) -> &[u8; PUBLIC_KEY_SIZE] {
+        &self.public_key
+    }
+
+    /// Address type carried after the public key.
+    pub fn address_type(&self) -> &AddressType {
+        &self.address_type
+    }
+
+    /// Payment data of an integrated address.
+    pub fn data(&self) -> Option<&DataElement> {
+        match &self.address_type {
+            AddressType::Normal => None,
+            AddressType::Data(data) => Some(data),
+        }
+    }
+
+    /// Serialized payment data of an integrated address.
+    pub fn data_bytes(&self) -> Option<&[u8]> {
+        self.data().map(|_| self.data_bytes.as_slice())
+    }
+
+    /// The normal address funds are actually sent to, without payment data.
+    pub fn to_normal(&self) -> Self {
+        Self {
+            network: self.network,
+            public_key: self.public_key,
+            address_type: AddressType::Normal,
+            data_bytes: Vec::new(),
+        }
+    }
+}
+
+impl std::str::FromStr for Address {
+    type Err = WalletError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Self::decode(address)
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Legacy wallet #1 from tests/regression_test.rs.
+    const PUBLIC_KEY: &str = "aa17efea5456e001f01a5dc58aee3d4077059faa7b49365c42dc30261f2d0b1c";
+    const MAINNET_ADDRESS: &str = "tos14gt7l6j52msqruq6thzc4m3agpmst8a20dynvhzzmsczv8edpvwqqxv22lu";
+    const TESTNET_ADDRESS: &str = "tst14gt7l6j52msqruq6thzc4m3agpmst8a20dynvhzzmsczv8edpvwqqaurths";
+
+    fn public_key() -> [u8; PUBLIC_KEY_SIZE] {
+        hex::decode(PUBLIC_KEY).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn addresses_round_trip_on_both_networks() {
+        for (network, encoded) in [(Network::Mainnet, MAINNET_ADDRESS), (Network::Testnet, TESTNET_ADDRESS)] {
+            let address = Address::new(network, public_key(), AddressType::Normal).unwrap();
+            assert_eq!(address.to_string(), encoded);
+
+            let decoded: Address = encoded.parse().unwrap();
+            assert_eq!(decoded, address);
+            assert_eq!(decoded.network(), network);
+            assert_eq!(decoded.public_key(), &public_key());
+            assert_eq!(decoded.address_type(), &AddressType::Normal);
+            assert_eq!(decoded.data(), None);
+        }
+
+        assert_eq!(Address::decode(&MAINNET_ADDRESS.to_uppercase()).unwrap().public_key(), &public_key());
+    }
+
+    #[test]
+    fn decode_rejects_malformed_addresses() {
+        let mut payload = public_key().to_vec();
+        payload.push(0);
+        let wrong_hrp = bech32::encode("btc", payload.to_base32(), Variant::Bech32).unwrap();
+        let bech32m = bech32::encode("tos", payload.to_base32(), Variant::Bech32m).unwrap();
+        let short = bech32::encode("tos", (&payload[1..]).to_base32(), Variant::Bech32).unwrap();
+        payload.push(7);
+        let normal_with_data = bech32::encode("tos", payload.to_base32(), Variant::Bech32).unwrap();
+        payload.truncate(PUBLIC_KEY_SIZE + 1);
+        payload[PUBLIC_KEY_SIZE] = 1;
+        let empty_data = bech32::encode("tos", payload.to_base32(), Variant::Bech32).unwrap();
+        payload.extend(DataElement::Array(vec![DataElement::Value(DataValue::U64(7)); 120]).to_bytes().unwrap());
+        let oversized_data = bech32::encode("tos", payload.to_base32(), Variant::Bech32).unwrap();
+        payload.truncate(PUBLIC_KEY_SIZE + 1);
+        payload[PUBLIC_KEY_SIZE] = 9;
+        let unknown_type = bech32::encode("tos", payload.to_base32(), Variant::Bech32).unwrap();
+        let mut bad_checksum = MAINNET_ADDRESS.to_string();
+        bad_checksum.pop();
+        bad_checksum.push('q');
+
+        for address in [
+            wrong_hrp,
+            bech32m,
+            short,
+            normal_with_data,
+            empty_data,
+            oversized_data,
+            unknown_type,
+            bad_checksum,
+            String::new(),
+        ] {
+            assert!(
+                matches!(Address::decode(&address), Err(WalletError::InvalidAddress(_))),
+                "accepted {address:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn integrated_addresses_round_trip_payment_data() {
+        let invoice = DataElement::Fields(vec![
+            (DataValue::String("invoice".to_string()), DataElement::Value(DataValue::U64(1042))),
+            (
+                DataValue::String("paid".to_string()),
+                DataElement::Array(vec![DataElement::Value(DataValue::Bool(false))]),
+            ),
+        ]);
+        assert_eq!(
+            hex::encode(invoice.to_bytes().unwrap()),
+            "02020107696e766f696365000500000000000004120104706169640101000000"
+        );
+
+        let largest = DataElement::Value(DataValue::String("m".repeat(255)));
+        for data in [DataElement::Value(DataValue::U8(0x2a)), invoice, largest] {
+            let address = Address::new(Network::Mainnet, public_key(), AddressType::Data(data.clone())).unwrap();
+            let encoded = address.encode();
+            assert!(encoded.starts_with("tos1"));
+
+            let decoded = Address::decode(&encoded).unwrap();
+            assert_eq!(decoded, address);
+            assert_eq!(decoded.data(), Some(&data));
+            assert_eq!(decoded.data_bytes(), Some(&data.to_bytes().unwrap()[..]));
+            assert_eq!(decoded.address_type().code(), "data");
+            assert_eq!(decoded.to_normal().encode(), MAINNET_ADDRESS);
+        }
+
+        let duplicate = DataElement::Fields(vec![
+            (DataValue::U8(1), DataElement::Value(DataValue::U8(1))),
+            (DataValue::U8(1), DataElement::Value(DataValue::U8(2))),
+        ]);
+        let oversized = DataElement::Array(vec![DataElement::Value(DataValue::Hash([0; HASH_SIZE])); 32]);
+        let long_string = DataElement::Value(DataValue::String("m".repeat(256)));
+        for data in [duplicate, oversized, long_string] {
+            assert!(matches!(
+                Address::new(Network::Testnet, public_key(), AddressType::Data(data)),
+                Err(WalletError::InvalidAddress(_))
+            ));
+        }
+
+        for bytes in ["", "00", "0002", "0000020100", "09", "0202020101020101", "00080000"] {
+            assert!(DataElement::from_bytes(&hex::decode(bytes).unwrap()).is_err(), "accepted {bytes}");
+        }
+
+        let nested = |depth| {
+            (0..depth).fold(DataElement::Value(DataValue::U8(0)), |inner, _| DataElement::Array(vec![inner]))
+        };
+        let deepest = nested(MAX_DATA_DEPTH).to_bytes().unwrap();
+        assert_eq!(DataElement::from_bytes(&deepest).unwrap(), nested(MAX_DATA_DEPTH));
+        let too_deep = format!("0101{}", hex::encode(&deepest));
+        assert!(matches!(
+            DataElement::from_bytes(&hex::decode(too_deep).unwrap()),
+            Err(WalletError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            Address::new(Network::Mainnet, public_key(), AddressType::Data(nested(MAX_DATA_DEPTH + 1))),
+            Err(WalletError::InvalidAddress(_))
+        ));
+    }
+}
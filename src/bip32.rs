@@ -0,0 +1,617 @@
+//! BIP32 hierarchical deterministic derivation for the TOS (secp256k1) key.
+
+use hmac::Mac;
+use k256::ecdsa::SigningKey;
+use k256::elliptic_curve::ff::PrimeField;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::subtle::CtOption;
+use k256::{ProjectivePoint, PublicKey, Scalar};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::{finalize_hmac_sha512, new_hmac_sha512, WalletError, DEFAULT_HD_PATH};
+
+/// Offset added to an index to mark it hardened.
+pub const HD_HARDENED_OFFSET: u32 = 0x8000_0000;
+const HD_MAX_DEPTH: usize = 255;
+const XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xAD, 0xE4];
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xB2, 0x1E];
+
+/// A parsed BIP32 derivation path such as `m/44'/60'/0'/0/0`.
+///
+/// Hardened segments may be written with either `'` or `h`. The canonical
+/// string form always uses `'`, and that form is what the UNO derivation
+/// hashes, so `m/44h/60h/0h/0/0` and `m/44'/60'/0'/0/0` yield the same wallets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath {
+    pub(crate) indices: Vec<u32>,
+}
+
+impl DerivationPath {
+    /// Parses an absolute path; see the type docs for the accepted syntax.
+    pub fn parse(path: &str) -> Result<Self, WalletError> {
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(WalletError::InvalidPath(format!(
+                "{path:?} must start with \"m/\""
+            )));
+        }
+
+        let mut indices = Vec::new();
+        for (position, segment) in segments.enumerate() {
+            let (digits, hardened) = match segment.strip_suffix(['\'', 'h', 'H']) {
+                Some(digits) => (digits, true),
+                None => (segment, false),
+            };
+
+            if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(WalletError::InvalidPath(format!(
+                    "segment {} ({segment:?}) must be a number optionally followed by ' or h",
+                    position + 1
+                )));
+            }
+
+            let index = digits
+                .parse::<u32>()
+                .ok()
+                .filter(|index| *index < HD_HARDENED_OFFSET)
+                .ok_or_else(|| {
+                    WalletError::InvalidPath(format!(
+                        "segment {} ({segment:?}) index must be below {HD_HARDENED_OFFSET}",
+                        position + 1
+                    ))
+                })?;
+
+            indices.push(if hardened { index + HD_HARDENED_OFFSET } else { index });
+        }
+
+        if indices.is_empty() {
+            return Err(WalletError::InvalidPath(format!(
+                "{path:?} must contain at least one segment"
+            )));
+        }
+
+        if indices.len() > HD_MAX_DEPTH {
+            return Err(WalletError::InvalidPath(format!(
+                "depth {} exceeds the BIP32 maximum of {HD_MAX_DEPTH}",
+                indices.len()
+            )));
+        }
+
+        Ok(Self { indices })
+    }
+
+    /// Child indices, with hardened ones offset by `0x8000_0000`.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// The ancestor path made of the first `depth` segments, if it exists.
+    ///
+    /// `truncate(3)` of a BIP44 path is its account node, `m/44'/60'/0'`.
+    pub fn truncate(&self, depth: usize) -> Option<Self> {
+        (1..=self.indices.len()).contains(&depth).then(|| Self {
+            indices: self.indices[..depth].to_vec(),
+        })
+    }
+}
+
+impl Default for DerivationPath {
+    fn default() -> Self {
+        Self::parse(DEFAULT_HD_PATH).expect("default HD path is valid")
+    }
+}
+
+impl std::str::FromStr for DerivationPath {
+    type Err = WalletError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+impl std::fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("m")?;
+        for index in &self.indices {
+            if *index >= HD_HARDENED_OFFSET {
+                write!(f, "/{}'", index - HD_HARDENED_OFFSET)?;
+            } else {
+                write!(f, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A BIP32 node holding a secp256k1 private key and its chain code.
+///
+/// The key and chain code are wiped when the node is dropped.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ExtendedPrivateKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    private_key: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    /// Master node `m` for a BIP39 seed.
+    pub fn from_seed(seed: &[u8]) -> Result<Self, WalletError> {
+        let (private_key, chain_code) = derive_bip32_master(seed)?;
+        Ok(Self {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code: *chain_code,
+            private_key: *private_key,
+        })
+    }
+
+    /// Node at `path` below the master node for `seed`.
+    pub fn derive(seed: &[u8], path: &DerivationPath) -> Result<Self, WalletError> {
+        path.indices()
+            .iter()
+            .try_fold(Self::from_seed(seed)?, |node, &index| node.derive_child(index))
+    }
+
+    /// Child node at `index`; indices at or above [`HD_HARDENED_OFFSET`] are hardened.
+    pub fn derive_child(&self, index: u32) -> Result<Self, WalletError> {
+        let (private_key, chain_code) =
+            derive_bip32_child(&self.private_key, &self.chain_code, index)?;
+        Ok(Self {
+            depth: self.depth.checked_add(1).ok_or_else(depth_overflow)?,
+            parent_fingerprint: self.fingerprint()?,
+            child_number: index,
+            chain_code: *chain_code,
+            private_key: *private_key,
+        })
+    }
+
+    /// Raw 32-byte private key.
+    pub fn private_key(&self) -> &[u8; 32] {
+        &self.private_key
+    }
+
+    /// Public counterpart carrying the same chain code and metadata.
+    pub fn extended_public_key(&self) -> Result<ExtendedPublicKey, WalletError> {
+        Ok(ExtendedPublicKey {
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            public_key: compressed_public_key(&self.private_key)?,
+        })
+    }
+
+    /// First four bytes of HASH160 of this node's compressed public key.
+    pub fn fingerprint(&self) -> Result<[u8; 4], WalletError> {
+        Ok(key_fingerprint(&compressed_public_key(&self.private_key)?))
+    }
+
+    /// Base58Check `xprv…` serialization.
+    pub fn to_xprv(&self) -> String {
+        let mut key = Zeroizing::new([0u8; 33]);
+        key[1..].copy_from_slice(&self.private_key);
+        serialize_extended_key(
+            XPRV_VERSION,
+            self.depth,
+            self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &key,
+        )
+    }
+}
+
+/// A BIP32 node holding only a compressed secp256k1 public key and chain code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtendedPublicKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    public_key: [u8; 33],
+}
+
+impl ExtendedPublicKey {
+    /// Parses a Base58Check `xpub…` string.
+    pub fn parse(xpub: &str) -> Result<Self, WalletError> {
+        let payload = bs58::decode(xpub.trim())
+            .with_check(None)
+            .into_vec()
+            .map_err(|err| WalletError::InvalidExtendedKey(err.to_string()))?;
+
+        if payload.len() != 78 {
+            return Err(WalletError::InvalidExtendedKey(format!(
+                "payload is {} bytes, expected 78",
+                payload.len()
+            )));
+        }
+
+        match payload[..4].try_into() {
+            Ok(XPUB_VERSION) => {}
+            Ok(XPRV_VERSION) => {
+                return Err(WalletError::InvalidExtendedKey(
+                    "expected an xpub, got an xprv".to_string(),
+                ))
+            }
+            _ => {
+                return Err(WalletError::InvalidExtendedKey(format!(
+                    "unknown version {}",
+                    hex::encode(&payload[..4])
+                )))
+            }
+        }
+
+        let mut key = Self {
+            depth: payload[4],
+            parent_fingerprint: [0; 4],
+            child_number: u32::from_be_bytes(payload[9..13].try_into().expect("4 bytes")),
+            chain_code: [0; 32],
+            public_key: [0; 33],
+        };
+        key.parent_fingerprint.copy_from_slice(&payload[5..9]);
+        key.chain_code.copy_from_slice(&payload[13..45]);
+        key.public_key.copy_from_slice(&payload[45..]);
+
+        if key.depth == 0 && (key.parent_fingerprint != [0; 4] || key.child_number != 0) {
+            return Err(WalletError::InvalidExtendedKey(
+                "master key has a parent fingerprint or child number".to_string(),
+            ));
+        }
+        parse_public_key(&key.public_key)?;
+
+        Ok(key)
+    }
+
+    /// Non-hardened child node at `index` (CKDpub); hardened indices need the
+    /// private key and are rejected.
+    pub fn derive_child(&self, index: u32) -> Result<Self, WalletError> {
+        let (public_key, chain_code) =
+            derive_bip32_public_child(&self.public_key, &self.chain_code, index)?;
+        Ok(Self {
+            depth: self.depth.checked_add(1).ok_or_else(depth_overflow)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            public_key,
+        })
+    }
+
+    /// Compressed SEC1 public key.
+    pub fn public_key(&self) -> &[u8; 33] {
+        &self.public_key
+    }
+
+    /// Uncompressed SEC1 public key, the form TOS addresses are hashed from.
+    pub fn uncompressed_public_key(&self) -> Result<Vec<u8>, WalletError> {
+        Ok(parse_public_key(&self.public_key)?
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec())
+    }
+
+    /// First four bytes of HASH160 of the public key.
+    pub fn fingerprint(&self) -> [u8; 4] {
+        key_fingerprint(&self.public_key)
+    }
+
+    /// Base58Check `xpub…` serialization.
+    pub fn to_xpub(&self) -> String {
+        serialize_extended_key(
+            XPUB_VERSION,
+            self.depth,
+            self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.public_key,
+        )
+    }
+}
+
+impl std::str::FromStr for ExtendedPublicKey {
+    type Err = WalletError;
+
+    fn from_str(xpub: &str) -> Result<Self, Self::Err> {
+        Self::parse(xpub)
+    }
+}
+
+fn serialize_extended_key(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: &[u8; 32],
+    key: &[u8; 33],
+) -> String {
+    let mut payload = Zeroizing::new(Vec::with_capacity(78));
+    payload.extend_from_slice(&version);
+    payload.push(depth);
+    payload.extend_from_slice(&parent_fingerprint);
+    payload.extend_from_slice(&child_number.to_be_bytes());
+    payload.extend_from_slice(chain_code);
+    payload.extend_from_slice(key);
+    bs58::encode(payload.as_slice()).with_check().into_string()
+}
+
+fn key_fingerprint(compressed_public_key: &[u8; 33]) -> [u8; 4] {
+    let hash = Ripemd160::digest(Sha256::digest(compressed_public_key));
+    let mut fingerprint = [0u8; 4];
+    fingerprint.copy_from_slice(&hash[..4]);
+    fingerprint
+}
+
+fn compressed_public_key(private_key: &[u8; 32]) -> Result<[u8; 33], WalletError> {
+    let encoded = secp256k1_public_key(private_key, true)?;
+    let mut public_key = [0u8; 33];
+    public_key.copy_from_slice(&encoded);
+    Ok(public_key)
+}
+
+fn parse_public_key(encoded: &[u8]) -> Result<PublicKey, WalletError> {
+    PublicKey::from_sec1_bytes(encoded).map_err(|_| {
+        WalletError::InvalidExtendedKey("public key is not a secp256k1 point".to_string())
+    })
+}
+
+fn depth_overflow() -> WalletError {
+    WalletError::InvalidPath(format!("depth exceeds the BIP32 maximum of {HD_MAX_DEPTH}"))
+}
+
+/// A private key and its chain code, wiped when dropped.
+type PrivateNode = (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>);
+
+pub(crate) fn derive_bip32_master(seed: &[u8]) -> Result<PrivateNode, WalletError> {
+    let mut mac = new_hmac_sha512(b"Bitcoin seed");
+    mac.update(seed);
+    let digest = finalize_hmac_sha512(mac);
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    let mut chain_code = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&digest[..32]);
+    chain_code.copy_from_slice(&digest[32..]);
+    bip32_scalar(key.as_slice())?;
+
+    Ok((key, chain_code))
+}
+
+pub(crate) fn derive_bip32_child(
+    parent_key: &[u8; 32],
+    parent_chain_code: &[u8; 32],
+    index: u32,
+) -> Result<PrivateNode, WalletError> {
+    let mut data = Zeroizing::new([0u8; 37]);
+
+    if index >= HD_HARDENED_OFFSET {
+        data[1..33].copy_from_slice(parent_key);
+    } else {
+        let compressed_public_key = secp256k1_public_key(parent_key, true)?;
+        data[..33].copy_from_slice(&compressed_public_key);
+    }
+
+    data[33..].copy_from_slice(&index.to_be_bytes());
+
+    let mut mac = new_hmac_sha512(parent_chain_code);
+    mac.update(data.as_slice());
+    let digest = finalize_hmac_sha512(mac);
+
+    let mut child_chain_code = Zeroizing::new([0u8; 32]);
+    child_chain_code.copy_from_slice(&digest[32..]);
+
+    let tweak = Zeroizing::new(bip32_scalar(&digest[..32])?);
+    let parent = Zeroizing::new(bip32_scalar(parent_key)?);
+    let derived = Zeroizing::new(*tweak + *parent);
+    if bool::from(derived.is_zero()) {
+        return Err(WalletError::ZeroKey);
+    }
+
+    let mut child_key = Zeroizing::new([0u8; 32]);
+    child_key.copy_from_slice(&derived.to_bytes());
+    Ok((child_key, child_chain_code))
+}
+
+pub(crate) fn derive_bip32_public_child(
+    parent_public_key: &[u8; 33],
+    parent_chain_code: &[u8; 32],
+    index: u32,
+) -> Result<([u8; 33], [u8; 32]), WalletError> {
+    if index >= HD_HARDENED_OFFSET {
+        return Err(WalletError::InvalidPath(format!(
+            "hardened index {}' cannot be derived from a public key",
+            index - HD_HARDENED_OFFSET
+        )));
+    }
+
+    let parent_point = parse_public_key(parent_public_key)?;
+
+    let mut data = [0u8; 37];
+    data[..33].copy_from_slice(parent_public_key);
+    data[33..].copy_from_slice(&index.to_be_bytes());
+
+    let mut mac = new_hmac_sha512(parent_chain_code);
+    mac.update(&data);
+    let digest = finalize_hmac_sha512(mac);
+
+    let mut child_chain_code = [0u8; 32];
+    child_chain_code.copy_from_slice(&digest[32..]);
+
+    let tweak = bip32_scalar(&digest[..32])?;
+    let child_point = ProjectivePoint::GENERATOR * tweak + parent_point.to_projective();
+    let child_public_key = PublicKey::from_affine(child_point.to_affine())
+        .map_err(|_| WalletError::ZeroKey)?;
+
+    let mut encoded = [0u8; 33];
+    encoded.copy_from_slice(child_public_key.to_encoded_point(true).as_bytes());
+
+    Ok((encoded, child_chain_code))
+}
+
+/// Parses a big-endian BIP32 scalar, rejecting zero and values not below the
+/// group order. The range check and the arithmetic on the result run in
+/// constant time; only the valid/invalid outcome is branched on.
+pub(crate) fn bip32_scalar(bytes: &[u8]) -> Result<Scalar, WalletError> {
+    let scalar = Scalar::from_repr(*k256::FieldBytes::from_slice(bytes))
+        .and_then(|scalar| CtOption::new(scalar, !scalar.is_zero()));
+    Option::from(scalar).ok_or(WalletError::ScalarOutOfRange)
+}
+
+pub(crate) fn secp256k1_public_key(
+    private_key: &[u8; 32],
+    compressed: bool,
+) -> Result<Vec<u8>, WalletError> {
+    let signing_key = SigningKey::from_bytes(private_key.into())
+        .map_err(|err| WalletError::InvalidPrivateKey(format!("secp256k1: {err}")))?;
+    let encoded_point = signing_key.verifying_key().to_encoded_point(compressed);
+    Ok(encoded_point.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP32 test vector 1.
+    const VECTOR_1_SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    #[test]
+    fn derivation_path_accepts_hardened_markers() {
+        let apostrophe = DerivationPath::parse("m/44'/60'/3'/0/7").unwrap();
+        let letter = DerivationPath::parse("m/44h/60H/3h/0/7").unwrap();
+
+        assert_eq!(apostrophe, letter);
+        assert_eq!(
+            apostrophe.indices(),
+            &[
+                HD_HARDENED_OFFSET + 44,
+                HD_HARDENED_OFFSET + 60,
+                HD_HARDENED_OFFSET + 3,
+                0,
+                7,
+            ]
+        );
+        assert_eq!(letter.to_string(), "m/44'/60'/3'/0/7");
+        assert_eq!(letter.truncate(3).unwrap().to_string(), "m/44'/60'/3'");
+        assert!(letter.truncate(0).is_none());
+        assert!(letter.truncate(6).is_none());
+    }
+
+    #[test]
+    fn derivation_path_rejects_malformed_segments() {
+        for path in [
+            "",
+            "m",
+            "m/",
+            "44'/60'/0'/0/0",
+            "m/44'//0",
+            "m/44''/0",
+            "m/-1",
+            "m/4a/0",
+            "m/2147483648",
+            "m/4294967296'",
+        ] {
+            assert!(
+                matches!(DerivationPath::parse(path), Err(WalletError::InvalidPath(_))),
+                "accepted {path:?}"
+            );
+        }
+
+        assert!(DerivationPath::parse("m/2147483647'").is_ok());
+        assert!(DerivationPath::parse(&format!("m{}", "/0".repeat(256))).is_err());
+    }
+
+    #[test]
+    fn bip32_scalar_bounds_are_typed_errors() {
+        let order =
+            hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").unwrap();
+        let mut below_order = order.clone();
+        below_order[31] -= 1;
+
+        assert_eq!(bip32_scalar(&[0u8; 32]), Err(WalletError::ScalarOutOfRange));
+        assert_eq!(bip32_scalar(&order), Err(WalletError::ScalarOutOfRange));
+        assert_eq!(bip32_scalar(&[0xff; 32]), Err(WalletError::ScalarOutOfRange));
+        assert!(bip32_scalar(&[1u8; 32]).is_ok());
+        assert_eq!(bip32_scalar(&below_order).unwrap(), -Scalar::ONE);
+    }
+
+    #[test]
+    fn extended_keys_match_bip32_vector_1() {
+        let seed = hex::decode(VECTOR_1_SEED).unwrap();
+
+        for (path, xprv, xpub) in [
+            (
+                None,
+                "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+                "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            ),
+            (
+                Some("m/0h"),
+                "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+                "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+            ),
+            (
+                Some("m/0h/1"),
+                "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+                "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+            ),
+        ] {
+            let node = match path {
+                None => ExtendedPrivateKey::from_seed(&seed).unwrap(),
+                Some(path) => ExtendedPrivateKey::derive(&seed, &path.parse().unwrap()).unwrap(),
+            };
+
+            assert_eq!(node.to_xprv(), xprv);
+            assert_eq!(node.extended_public_key().unwrap().to_xpub(), xpub);
+            assert_eq!(ExtendedPublicKey::parse(xpub).unwrap().to_xpub(), xpub);
+        }
+    }
+
+    #[test]
+    fn extended_private_key_zeroizes_its_secrets() {
+        let seed = hex::decode(VECTOR_1_SEED).unwrap();
+        let mut node = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        assert_ne!(node.private_key(), &[0u8; 32]);
+
+        node.zeroize();
+        assert_eq!(node.private_key(), &[0u8; 32]);
+        assert_eq!(node.chain_code, [0u8; 32]);
+    }
+
+    #[test]
+    fn public_child_derivation_matches_private_derivation() {
+        let seed = hex::decode(VECTOR_1_SEED).unwrap();
+        let account = ExtendedPrivateKey::derive(&seed, &"m/0h".parse().unwrap()).unwrap();
+        let account_xpub = account.extended_public_key().unwrap();
+
+        for index in [0, 1, 7, HD_HARDENED_OFFSET - 1] {
+            assert_eq!(
+                account_xpub.derive_child(index).unwrap(),
+                account.derive_child(index).unwrap().extended_public_key().unwrap(),
+                "index {index}"
+            );
+        }
+
+        assert!(matches!(
+            account_xpub.derive_child(HD_HARDENED_OFFSET),
+            Err(WalletError::InvalidPath(_))
+        ));
+    }
+
+    #[test]
+    fn xpub_parsing_rejects_malformed_input() {
+        let seed = hex::decode(VECTOR_1_SEED).unwrap();
+        let master = ExtendedPrivateKey::from_seed(&seed).unwrap();
+        let xpub = master.extended_public_key().unwrap().to_xpub();
+        let mut corrupted = xpub.clone();
+        corrupted.replace_range(20..21, if &xpub[20..21] == "a" { "b" } else { "a" });
+
+        for input in [master.to_xprv(), corrupted, "xpub".to_string(), String::new()] {
+            assert!(
+                matches!(ExtendedPublicKey::parse(&input), Err(WalletError::InvalidExtendedKey(_))),
+                "accepted {input:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! Codex32 (BIP93) strings for recovery phrase entropy.
+//!
+//! A codex32 string is `MS1`, a threshold digit, a 4-character identifier, a
+//! share index, the secret as bech32 characters and a 13-character BCH
+//! checksum that can be computed and verified by hand with the BIP93 paper
+//! worksheets. A threshold of `0` marks an unshared secret, which always has
+//! share index `S`. For a `k`-of-`n` split (`k` from 2 to 9) the secret is
+//! the share at index `S` of a degree `k - 1` polynomial over GF(32), and any
+//! `k` of the printed shares recover it.
+//!
+//! Strings are printed in uppercase, which suits hand copying and QR
+//! alphanumeric mode; either case is accepted, but not a mix. Only short
+//! strings (up to 93 data characters, so secrets of 16 to 46 bytes) are
+//! supported, which covers every BIP39 entropy size.
+
+use zeroize::Zeroizing;
+
+use crate::WalletError;
+
+/// Largest number of shares in a split: every index except `S`.
+pub const MAX_CODEX32_SHARE_COUNT: u8 = 31;
+/// Largest share threshold, the single digit in the header.
+pub const MAX_CODEX32_THRESHOLD: u8 = 9;
+
+const HRP: &str = "ms1";
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const SHARE_INDICES: &[u8; 31] = b"acdefghjklmnpqrtuvwxyz023456789";
+const SECRET_INDEX: u8 = b's';
+const IDENTIFIER_LENGTH: usize = 4;
+const HEADER_LENGTH: usize = 2 + IDENTIFIER_LENGTH;
+const CHECKSUM_LENGTH: usize = 13;
+const MAX_DATA_LENGTH: usize = 93;
+const MIN_SECRET_SIZE: usize = 16;
+const POLYMOD_RESIDUE: u128 = 0x23181b3;
+const CHECKSUM_CONSTANT: u128 = 0x10ce0795c2fd1e62a;
+const POLYMOD_GENERATOR: [u128; 5] = [
+    0x19dc500ce73fde210,
+    0x1bfae00def77fe529,
+    0x1fbd920fffe7bee52,
+    0x1739640bdeee3fdad,
+    0x07729a039cfc75f5a,
+];
+
+/// One codex32 string: an unshared secret or one share of a split.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Codex32Share {
+    /// 5-bit values of everything after `ms1`, checksum included.
+    data: Zeroizing<Vec<u8>>,
+}
+
+impl Codex32Share {
+    /// Encodes an unshared secret (threshold `0`, share index `S`).
+    pub fn from_secret(secret: &[u8], identifier: &str) -> Result<Self, WalletError> {
+        let identifier = parse_identifier(identifier)?;
+        Self::encode(b'0', &identifier, SECRET_INDEX, secret)
+    }
+
+    /// Parses and checksums a codex32 string.
+    pub fn parse(encoded: &str) -> Result<Self, WalletError> {
+        let encoded = Zeroizing::new(encoded.split_whitespace().collect::<String>());
+        if encoded.bytes().any(|byte| byte.is_ascii_uppercase()) && encoded.bytes().any(|byte| byte.is_ascii_lowercase()) {
+            return Err(WalletError::InvalidShare("codex32 strings must not mix upper and lower case".to_string()));
+        }
+        let lower = Zeroizing::new(encoded.to_ascii_lowercase());
+        let body = lower
+            .strip_prefix(HRP)
+            .ok_or_else(|| WalletError::InvalidShare("codex32 strings start with MS1".to_string()))?;
+
+        let data = Zeroizing::new(
+            body.chars()
+                .map(|character| {
+                    u8::try_from(character)
+                        .ok()
+                        .and_then(|byte| CHARSET.iter().position(|&c| c == byte))
+                        .map(|value| value as u8)
+                        .ok_or_else(|| {
+                            WalletError::InvalidShare(format!("character {character:?} is not valid in codex32"))
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        if data.len() > MAX_DATA_LENGTH {
+            return Err(WalletError::InvalidShare(
+                "long codex32 strings (secrets over 46 bytes) are not supported".to_string(),
+            ));
+        }
+        let payload_length = data.len().saturating_sub(HEADER_LENGTH + CHECKSUM_LENGTH);
+        if payload_length * 5 / 8 < MIN_SECRET_SIZE || payload_length * 5 % 8 > 4 {
+            return Err(WalletError::InvalidShare(format!("{} characters is not a valid codex32 length", encoded.len())));
+        }
+        if polymod(&data) != CHECKSUM_CONSTANT {
+            return Err(WalletError::InvalidShare(
+                "checksum does not match; check each character against the original".to_string(),
+            ));
+        }
+
+        let share = Self { data };
+        let threshold = share.threshold_char();
+        if threshold != b'0' && !(b'2'..=b'9').contains(&threshold) {
+            return Err(WalletError::InvalidShare(format!(
+                "threshold {:?} must be 0 or 2 to 9",
+                char::from(threshold)
+            )));
+        }
+        if threshold == b'0' && share.index_char() != SECRET_INDEX {
+            return Err(WalletError::InvalidShare("unshared secrets must have share index S".to_string()));
+        }
+        Ok(share)
+    }
+
+    /// Shares needed to recover the secret; 1 for an unshared secret.
+    pub fn threshold(&self) -> u8 {
+        match self.threshold_char() {
+            b'0' => 1,
+            digit => digit - b'0',
+        }
+    }
+
+    /// Uppercase 4-character identifier shared by all shares of a secret.
+    pub fn identifier(&self) -> String {
+        self.data[1..HEADER_LENGTH - 1]
+            .iter()
+            .map(|&value| char::from(CHARSET[usize::from(value)].to_ascii_uppercase()))
+            .collect()
+    }
+
+    /// Uppercase share index; `S` is the secret itself.
+    pub fn share_index(&self) -> char {
+        char::from(self.index_char().to_ascii_uppercase())
+    }
+
+    /// Decoded payload. For share index `S` this is the secret.
+    pub fn payload(&self) -> Zeroizing<Vec<u8>> {
+        let values = &self.data[HEADER_LENGTH..self.data.len() - CHECKSUM_LENGTH];
+        let mut bytes = Zeroizing::new(Vec::with_capacity(values.len() * 5 / 8));
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        for &value in values {
+            buffer = buffer << 5 | u16::from(value);
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        bytes
+    }
+
+    fn encode(threshold: u8, identifier: &[u8], index: u8, secret: &[u8]) -> Result<Self, WalletError> {
+        let payload_length = (secret.len() * 8).div_ceil(5);
+        if secret.len() < MIN_SECRET_SIZE || HEADER_LENGTH + payload_length + CHECKSUM_LENGTH > MAX_DATA_LENGTH {
+            return Err(WalletError::InvalidOptions(format!(
+                "codex32 secrets are 16 to 46 bytes, got {}",
+                secret.len()
+            )));
+        }
+
+        let mut data = Zeroizing::new(Vec::with_capacity(HEADER_LENGTH + payload_length + CHECKSUM_LENGTH));
+        data.push(value_of(threshold));
+        data.extend(identifier.iter().map(|&c| value_of(c)));
+        data.push(value_of(index));
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        for &byte in secret {
+            buffer = buffer << 8 | u16::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                data.push((buffer >> bits & 31) as u8);
+            }
+            buffer &= (1 << bits) - 1;
+        }
+        if bits > 0 {
+            data.push((buffer << (5 - bits) & 31) as u8);
+        }
+        Ok(Self::with_checksum(data))
+    }
+
+    /// Appends the checksum to header and payload values.
+    fn with_checksum(mut data: Zeroizing<Vec<u8>>) -> Self {
+        let len = data.len();
+        data.extend([0; CHECKSUM_LENGTH]);
+        let checksum = polymod(&data) ^ CHECKSUM_CONSTANT;
+        for (offset, value) in data[len..].iter_mut().enumerate() {
+            *value = (checksum >> (5 * (CHECKSUM_LENGTH - 1 - offset)) & 31) as u8;
+        }
+        Self { data }
+    }
+
+    fn threshold_char(&self) -> u8 {
+        CHARSET[usize::from(self.data[0])]
+    }
+
+    fn index_char(&self) -> u8 {
+        CHARSET[usize::from(self.data[HEADER_LENGTH - 1])]
+    }
+}
+
+impl std::str::FromStr for Codex32Share {
+    type Err = WalletError;
+
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        Self::parse(encoded)
+    }
+}
+
+impl std::fmt::Display for Codex32Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&HRP.to_ascii_uppercase())?;
+        for &value in self.data.iter() {
+            write!(f, "{}", char::from(CHARSET[usize::from(value)].to_ascii_uppercase()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits `secret` into `count` codex32 shares, any `threshold` of which
+/// recover it. A threshold of 1 returns the unshared secret string and
+/// requires `count == 1`. A missing `identifier` is chosen at random.
+pub fn split_codex32(
+    secret: &[u8],
+    threshold: u8,
+    count: u8,
+    identifier: Option<&str>,
+) -> Result<Vec<Codex32Share>, WalletError> {
+    let identifier = match identifier {
+        Some(identifier) => parse_identifier(identifier)?,
+        None => {
+            let mut random = [0u8; IDENTIFIER_LENGTH];
+            getrandom::getrandom(&mut random).map_err(|err| WalletError::Entropy(err.to_string()))?;
+            random.map(|byte| CHARSET[usize::from(byte & 31)])
+        }
+    };
+    if threshold == 1 && count == 1 {
+        return Ok(vec![Codex32Share::encode(b'0', &identifier, SECRET_INDEX, secret)?]);
+    }
+    if !(2..=MAX_CODEX32_THRESHOLD).contains(&threshold) || !(threshold..=MAX_CODEX32_SHARE_COUNT).contains(&count) {
+        return Err(WalletError::InvalidOptions(format!(
+            "{threshold}-of-{count} is not a codex32 split: use 1-of-1, or a threshold of 2 to \
+             {MAX_CODEX32_THRESHOLD} with at most {MAX_CODEX32_SHARE_COUNT} shares"
+        )));
+    }
+
+    let threshold_char = b'0' + threshold;
+    let secret_share = Codex32Share::encode(threshold_char, &identifier, SECRET_INDEX, secret)?;
+    let mut shares = SHARE_INDICES[..usize::from(threshold) - 1]
+        .iter()
+        .map(|&index| {
+            let mut random = Zeroizing::new(vec![0u8; secret.len()]);
+            getrandom::getrandom(&mut random).map_err(|err| WalletError::Entropy(err.to_string()))?;
+            Codex32Share::encode(threshold_char, &identifier, index, &random)
+        })
+        .collect::<Result<Vec<_>, WalletError>>()?;
+
+    let mut base = shares.iter().map(|share| share.data.as_slice()).collect::<Vec<_>>();
+    base.push(&secret_share.data);
+    let derived = SHARE_INDICES[usize::from(threshold) - 1..usize::from(count)]
+        .iter()
+        .map(|&index| Codex32Share {
+            data: interpolate(&base, value_of(index)),
+        })
+        .collect::<Vec<_>>();
+    shares.extend(derived);
+    Ok(shares)
+}
+
+/// Recovers the secret from codex32 strings: the unshared secret, the `S`
+/// share, or at least `threshold` distinct shares of one split. Extra shares
+/// must agree with the others.
+pub fn combine_codex32<S: AsRef<str>>(encoded: &[S]) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+    let mut shares: Vec<Codex32Share> = Vec::with_capacity(encoded.len());
+    for (position, encoded) in encoded.iter().enumerate() {
+        let share = Codex32Share::parse(encoded.as_ref()).map_err(|err| match err {
+            WalletError::InvalidShare(reason) => WalletError::InvalidShare(format!("share {}: {reason}", position + 1)),
+            other => other,
+        })?;
+        if let Some(first) = shares.first() {
+            if share.data[..HEADER_LENGTH - 1] != first.data[..HEADER_LENGTH - 1] || share.data.len() != first.data.len() {
+                return Err(WalletError::InvalidShare(format!(
+                    "share {} has a different threshold, identifier or length",
+                    position + 1
+                )));
+            }
+        }
+        match shares.iter().find(|other| other.index_char() == share.index_char()) {
+            Some(other) if *other != share => {
+                return Err(WalletError::InvalidShare(format!(
+                    "two different shares have index {}",
+                    share.share_index()
+                )));
+            }
+            Some(_) => {}
+            None => shares.push(share),
+        }
+    }
+
+    let first = shares
+        .first()
+        .ok_or_else(|| WalletError::InvalidShare("no shares given".to_string()))?;
+    let threshold = usize::from(first.threshold());
+    if let Some(secret) = shares.iter().find(|share| share.index_char() == SECRET_INDEX) {
+        return Ok(secret.payload());
+    }
+    if shares.len() < threshold {
+        return Err(WalletError::InvalidShare(format!(
+            "{} shares given, {threshold} are needed",
+            shares.len()
+        )));
+    }
+
+    let base = shares[..threshold].iter().map(|share| share.data.as_slice()).collect::<Vec<_>>();
+    for extra in &shares[threshold..] {
+        if interpolate(&base, extra.data[HEADER_LENGTH - 1]) != extra.data {
+            return Err(WalletError::InvalidShare(format!(
+                "share {} does not fit the others; check that they come from the same backup",
+                extra.share_index()
+            )));
+        }
+    }
+    let secret = Codex32Share {
+        data: interpolate(&base, value_of(SECRET_INDEX)),
+    };
+    Ok(secret.payload())
+}
+
+fn parse_identifier(identifier: &str) -> Result<[u8; IDENTIFIER_LENGTH], WalletError> {
+    let lower = identifier.trim().to_ascii_lowercase();
+    match <[u8; IDENTIFIER_LENGTH]>::try_from(lower.as_bytes()) {
+        Ok(identifier) if identifier.iter().all(|c| CHARSET.contains(c)) => Ok(identifier),
+        _ => Err(WalletError::InvalidOptions(format!(
+            "codex32 identifier {identifier:?} must be 4 bech32 characters"
+        ))),
+    }
+}
+
+fn value_of(character: u8) -> u8 {
+    CHARSET
+        .iter()
+        .position(|&c| c == character)
+        .expect("characters come from the codex32 alphabet") as u8
+}
+
+fn polymod(values: &[u8]) -> u128 {
+    let mut residue = POLYMOD_RESIDUE;
+    for &value in values {
+        let top = residue >> 60;
+        residue = (residue & 0x0fff_ffff_ffff_ffff) << 5 ^ u128::from(value);
+        for (bit, generator) in POLYMOD_GENERATOR.iter().enumerate() {
+            if top >> bit & 1 == 1 {
+                residue ^= generator;
+            }
+        }
+    }
+    residue
+}
+
+/// Multiplication in GF(32) modulo `x^5 + x^3 + 1`.
+fn gf32_mul(a: u8, b: u8) -> u8 {
+    let mut product = 0u16;
+    for bit in 0..5 {
+        if b >> bit & 1 == 1 {
+            product ^= u16::from(a) << bit;
+        }
+    }
+    for bit in (5..9).rev() {
+        if product >> bit & 1 == 1 {
+            product ^= 0x29 << (bit - 5);
+        }
+    }
+    product as u8
+}
+
+fn gf32_inv(a: u8) -> u8 {
+    (0..29).fold(a, |power, _| gf32_mul(power, a))
+}
+
+/// Lagrange interpolation at share index `x` over GF(32), character by
+/// character. The shares must have distinct indices and equal lengths.
+fn interpolate(shares: &[&[u8]], x: u8) -> Zeroizing<Vec<u8>> {
+    let indices = shares.iter().map(|share| share[HEADER_LENGTH - 1]).collect::<Vec<_>>();
+    let mut result = Zeroizing::new(vec![0u8; shares[0].len()]);
+    for (i, share) in shares.iter().enumerate() {
+        let (numerator, denominator) = indices
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m != i)
+            .fold((1, 1), |(numerator, denominator), (_, &other)| {
+                (gf32_mul(numerator, x ^ other), gf32_mul(denominator, indices[i] ^ other))
+            });
+        let coefficient = gf32_mul(numerator, gf32_inv(denominator));
+        for (output, &value) in result.iter_mut().zip(share.iter()) {
+            *output ^= gf32_mul(value, coefficient);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP93 test vectors 1 to 4.
+    const VECTOR_1: &str = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+    const VECTOR_2_A: &str = "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM";
+    const VECTOR_2_C: &str = "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN";
+    const VECTOR_2_D: &str = "MS12NAMEDLL4F8JLH4E5VDVULDLFXU2JHDNLSM97XVENRXEG";
+    const VECTOR_2_S: &str = "MS12NAMES6XQGUZTTXKEQNJSJZV4JV3NZ5K3KWGSPHUH6EVW";
+    const VECTOR_3_S: &str = "ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln";
+    const VECTOR_4: &str = "ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma";
+
+    #[test]
+    fn bip93_vectors_decode() {
+        let share = Codex32Share::parse(VECTOR_1).unwrap();
+        assert_eq!((share.threshold(), share.identifier().as_str(), share.share_index()), (1, "TEST", 'S'));
+        assert_eq!(hex::encode(combine_codex32(&[VECTOR_1]).unwrap()), "318c6318c6318c6318c6318c6318c631");
+        assert_eq!(share.to_string(), VECTOR_1.to_uppercase());
+
+        let a = Codex32Share::parse(VECTOR_2_A).unwrap();
+        let c = Codex32Share::parse(VECTOR_2_C).unwrap();
+        assert_eq!(interpolate(&[&a.data, &c.data], value_of(b'd')), Codex32Share::parse(VECTOR_2_D).unwrap().data);
+        assert_eq!(
+            hex::encode(combine_codex32(&[VECTOR_2_C, VECTOR_2_A]).unwrap()),
+            "d1808e096b35b209ca12132b264662a5"
+        );
+        assert_eq!(combine_codex32(&[VECTOR_2_S]).unwrap(), combine_codex32(&[VECTOR_2_A, VECTOR_2_D]).unwrap());
+        assert_eq!(combine_codex32(&[VECTOR_2_A, VECTOR_2_C, VECTOR_2_D]).unwrap().len(), 16);
+
+        assert_eq!(
+            hex::encode(Codex32Share::parse(VECTOR_3_S).unwrap().payload()),
+            "ffeeddccbbaa99887766554433221100"
+        );
+        assert_eq!(
+            hex::encode(combine_codex32(&[VECTOR_4]).unwrap()),
+            "ffeeddccbbaa99887766554433221100ffeeddccbbaa99887766554433221100"
+        );
+    }
+
+    #[test]
+    fn shares_split_and_recover() {
+        let secret = [0xa5u8; 32];
+        let shares = split_codex32(&secret, 3, 5, Some("cash")).unwrap();
+        let encoded = shares.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            encoded.iter().map(|share| &share[..9]).collect::<Vec<_>>(),
+            ["MS13CASHA", "MS13CASHC", "MS13CASHD", "MS13CASHE", "MS13CASHF"]
+        );
+        assert!(encoded.iter().all(|share| share.len() == 74));
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset = subset.map(|i| encoded[i].to_lowercase());
+            assert_eq!(combine_codex32(&subset).unwrap().as_slice(), secret);
+        }
+        assert!(matches!(combine_codex32(&encoded[..2]), Err(WalletError::InvalidShare(_))));
+
+        let unshared = split_codex32(&secret[..16], 1, 1, None).unwrap();
+        assert_eq!(unshared[0].share_index(), 'S');
+        assert_eq!(unshared[0].to_string().len(), 48);
+        assert_eq!(combine_codex32(&[unshared[0].to_string()]).unwrap().as_slice(), &secret[..16]);
+    }
+
+    #[test]
+    fn malformed_strings_and_splits_are_rejected() {
+        let mut typo = VECTOR_2_A.to_string();
+        typo.replace_range(20..21, "Q");
+        for bad in [
+            typo.as_str(),
+            "Ms12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM",
+            "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQR",
+            "bc12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM",
+        ] {
+            assert!(matches!(Codex32Share::parse(bad), Err(WalletError::InvalidShare(_))), "accepted {bad:?}");
+        }
+        let other_split = split_codex32(&[7; 16], 2, 2, Some("name")).unwrap()[0].to_string();
+        assert!(matches!(combine_codex32(&[VECTOR_2_A, VECTOR_2_C, &other_split]), Err(WalletError::InvalidShare(_))));
+
+        for (threshold, count, identifier) in [(1, 2, None), (2, 1, None), (10, 12, None), (2, 32, None), (2, 3, Some("abc"))] {
+            assert!(matches!(
+                split_codex32(&[1; 16], threshold, count, identifier),
+                Err(WalletError::InvalidOptions(_))
+            ));
+        }
+        assert!(split_codex32(&[1; 15], 1, 1, None).is_err());
+    }
+}
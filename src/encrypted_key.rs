@@ -0,0 +1,306 @@
+//! Password-encrypted private keys for printed paper wallets, in the spirit
+//! of BIP38.
+//!
+//! The printed string is Base58Check over
+//!
+//! ```text
+//! prefix (3) || address_hash (4) || ciphertext (32) || tag (16)
+//! ```
+//!
+//! and is always 80 characters, starting with `TEK` for TOS keys and `UEK`
+//! for UNO keys. `address_hash` is the first four bytes of
+//! `SHA256(SHA256(address))` over the checksummed `0x` address, so the string
+//! can be matched to a wallet without the password. The key is stretched with
+//! scrypt (`n = 2^14, r = 8, p = 8`, as in BIP38) over the NFC-normalized
+//! password and salted with `prefix || address_hash`; the first 32 bytes key
+//! AES-256-CTR and the last 32 bytes key an HMAC-SHA256 tag over everything
+//! before it. A wrong password fails the tag; a mistyped character fails the
+//! Base58Check checksum.
+//!
+//! Encryption is deterministic on purpose, as in BIP38's non-EC mode: there
+//! is no random salt or IV, and AES-256-CTR starts from an all-zero counter.
+//! Each key is encrypted with a key stream derived from its own address and
+//! the password, so a stream is never reused for different plaintexts. The
+//! same key and password always give the same string, which shows only that
+//! two printouts hold the same key under the same password; the address hash
+//! already links them to the same wallet. In exchange the string stays a
+//! fixed 80 characters, and reprinting a wallet reproduces it exactly.
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::subtle::ConstantTimeEq;
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use crate::{tos_wallet_entry, uno_wallet_entry, WalletEntry, WalletError};
+
+type Aes256Ctr = ctr::Ctr128BE<aes::Aes256>;
+
+const TOS_PREFIX: [u8; 3] = [0x0b, 0x43, 0x89];
+const UNO_PREFIX: [u8; 3] = [0x0b, 0xb1, 0x78];
+const ADDRESS_HASH_SIZE: usize = 4;
+const TAG_SIZE: usize = 16;
+const PAYLOAD_SIZE: usize = 3 + ADDRESS_HASH_SIZE + 32 + TAG_SIZE;
+const SCRYPT_LOG_N: u8 = 14;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 8;
+
+/// Which wallet key an encrypted key string holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncryptedKeyKind {
+    /// TOS secp256k1 key from [`crate::derive_tos_wallet`].
+    Tos,
+    /// UNO Ristretto key from [`crate::derive_uno_wallet`].
+    Uno,
+}
+
+impl EncryptedKeyKind {
+    /// Parses `"tos"` or `"uno"`.
+    pub fn parse(code: &str) -> Result<Self, WalletError> {
+        match code.trim().to_ascii_lowercase().as_str() {
+            "tos" => Ok(Self::Tos),
+            "uno" => Ok(Self::Uno),
+            _ => Err(WalletError::InvalidOptions(format!(
+                "unknown key kind {code:?}: must be \"tos\" or \"uno\""
+            ))),
+        }
+    }
+
+    /// Lowercase code, `tos` or `uno`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Tos => "tos",
+            Self::Uno => "uno",
+        }
+    }
+
+    fn prefix(self) -> [u8; 3] {
+        match self {
+            Self::Tos => TOS_PREFIX,
+            Self::Uno => UNO_PREFIX,
+        }
+    }
+
+    fn wallet_entry(self, private_key: &[u8; 32]) -> Result<WalletEntry, WalletError> {
+        match self {
+            Self::Tos => tos_wallet_entry(private_key),
+            Self::Uno => uno_wallet_entry(private_key),
+        }
+    }
+}
+
+/// A password-encrypted TOS or UNO private key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedKey {
+    kind: EncryptedKeyKind,
+    address_hash: [u8; ADDRESS_HASH_SIZE],
+    ciphertext: [u8; 32],
+    tag: [u8; TAG_SIZE],
+}
+
+impl EncryptedKey {
+    /// Encrypts a private key under `password`. Fails if the key is not
+    /// valid for `kind`.
+    pub fn encrypt(kind: EncryptedKeyKind, private_key: &[u8; 32], password: &str) -> Result<Self, WalletError> {
+        let entry = kind.wallet_entry(private_key)?;
+        let address_hash = address_hash(entry.address());
+        let keys = derive_keys(kind, &address_hash, password);
+
+        let mut ciphertext = *private_key;
+        Aes256Ctr::new(keys[..32].into(), &[0u8; 16].into()).apply_keystream(&mut ciphertext);
+
+        let mut encrypted = Self {
+            kind,
+            address_hash,
+            ciphertext,
+            tag: [0; TAG_SIZE],
+        };
+        encrypted.tag = encrypted.compute_tag(&keys);
+        Ok(encrypted)
+    }
+
+    /// Parses a `TEK…` or `UEK…` string, rejecting any checksum mismatch.
+    pub fn parse(encoded: &str) -> Result<Self, WalletError> {
+        let payload = bs58::decode(encoded.trim())
+            .with_check(None)
+            .into_vec()
+            .map_err(|err| WalletError::InvalidEncryptedKey(err.to_string()))?;
+        if payload.len() != PAYLOAD_SIZE {
+            return Err(WalletError::InvalidEncryptedKey(format!(
+                "payload is {} bytes, expected {PAYLOAD_SIZE}",
+                payload.len()
+            )));
+        }
+
+        let kind = match payload[..3] {
+            [0x0b, 0x43, 0x89] => EncryptedKeyKind::Tos,
+            [0x0b, 0xb1, 0x78] => EncryptedKeyKind::Uno,
+            _ => return Err(WalletError::InvalidEncryptedKey("unknown prefix".to_string())),
+        };
+        let (address_hash, rest) = payload[3..].split_at(ADDRESS_HASH_SIZE);
+        let (ciphertext, tag) = rest.split_at(32);
+
+        Ok(Self {
+            kind,
+            address_hash: address_hash.try_into().expect("split at the address hash size"),
+            ciphertext: ciphertext.try_into().expect("split at the key size"),
+            tag: tag.try_into().expect("remainder is the tag"),
+        })
+    }
+
+    /// Base58Check string to print.
+    pub fn encode(&self) -> String {
+        bs58::encode(self.authenticated_bytes()).with_check().into_string()
+    }
+
+    /// Decrypts the key and returns its wallet entry. A wrong password is
+    /// [`WalletError::WrongPassword`].
+    pub fn decrypt(&self, password: &str) -> Result<WalletEntry, WalletError> {
+        let keys = derive_keys(self.kind, &self.address_hash, password);
+        if !bool::from(self.compute_tag(&keys).ct_eq(&self.tag)) {
+            return Err(WalletError::WrongPassword);
+        }
+
+        let mut private_key = Zeroizing::new(self.ciphertext);
+        Aes256Ctr::new(keys[..32].into(), &[0u8; 16].into()).apply_keystream(private_key.as_mut_slice());
+
+        let entry = self.kind.wallet_entry(&private_key)?;
+        if !self.matches_address(entry.address()) {
+            return Err(WalletError::InvalidEncryptedKey(
+                "decrypted key does not match the address hash".to_string(),
+            ));
+        }
+        Ok(entry)
+    }
+
+    /// Key kind selected by the prefix.
+    pub fn kind(&self) -> EncryptedKeyKind {
+        self.kind
+    }
+
+    /// Whether this string belongs to `address`, checked without the password.
+    pub fn matches_address(&self, address: &str) -> bool {
+        address_hash(address.trim()) == self.address_hash
+    }
+
+    fn authenticated_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(PAYLOAD_SIZE);
+        bytes.extend_from_slice(&self.kind.prefix());
+        bytes.extend_from_slice(&self.address_hash);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes.extend_from_slice(&self.tag);
+        bytes
+    }
+
+    fn compute_tag(&self, keys: &[u8; 64]) -> [u8; TAG_SIZE] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&keys[32..]).expect("HMAC accepts keys of any length");
+        mac.update(&self.authenticated_bytes()[..PAYLOAD_SIZE - TAG_SIZE]);
+        let mut tag = [0u8; TAG_SIZE];
+        tag.copy_from_slice(&mac.finalize().into_bytes()[..TAG_SIZE]);
+        tag
+    }
+}
+
+impl std::str::FromStr for EncryptedKey {
+    type Err = WalletError;
+
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        Self::parse(encoded)
+    }
+}
+
+impl std::fmt::Display for EncryptedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/// First four bytes of `SHA256(SHA256(address))`; the address is checksummed
+/// by the wallet, so case is significant.
+fn address_hash(address: &str) -> [u8; ADDRESS_HASH_SIZE] {
+    let digest = Sha256::digest(Sha256::digest(address.as_bytes()));
+    let mut hash = [0u8; ADDRESS_HASH_SIZE];
+    hash.copy_from_slice(&digest[..ADDRESS_HASH_SIZE]);
+    hash
+}
+
+fn derive_keys(kind: EncryptedKeyKind, address_hash: &[u8; ADDRESS_HASH_SIZE], password: &str) -> Zeroizing<[u8; 64]> {
+    let password = Zeroizing::new(password.nfc().collect::<String>());
+    let mut salt = [0u8; 3 + ADDRESS_HASH_SIZE];
+    salt[..3].copy_from_slice(&kind.prefix());
+    salt[3..].copy_from_slice(address_hash);
+
+    let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 64).expect("fixed scrypt parameters are valid");
+    let mut keys = Zeroizing::new([0u8; 64]);
+    scrypt::scrypt(password.as_bytes(), &salt, &params, keys.as_mut_slice()).expect("output length is valid");
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TOS and UNO keys of the `abandon … art` test mnemonic.
+    const TOS_PRIVATE_KEY: &str = "1053fae1b3ac64f178bcc21026fd06a3f4544ec2f35338b001f02d1d8efa3d5f";
+    const TOS_ADDRESS: &str = "0x9c57F3A17e19682cD9197A1AF278CF59f82Edcf871d630F28EcC8056F25c1CDb";
+    const UNO_PRIVATE_KEY: &str = "185721a1028bc0a87b5fb788bf9223e8ee6a8426b241ce2447920a77c9759207";
+    const UNO_ADDRESS: &str = "0xeA1A1B540dD3794C33fC8EBD0ce1ed3b4Eb9FD5905fD129D6F030f98911178a2";
+    // "Ünïcode" typed precomposed (NFC) and decomposed (NFD).
+    const PASSWORD_NFC: &str = "\u{dc}n\u{ef}code";
+    const PASSWORD_NFD: &str = "U\u{308}ni\u{308}code";
+
+    fn key(hex_key: &str) -> [u8; 32] {
+        hex::decode(hex_key).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn keys_round_trip_through_the_printed_string() {
+        for (kind, private_key, address, prefix) in [
+            (EncryptedKeyKind::Tos, TOS_PRIVATE_KEY, TOS_ADDRESS, "TEK"),
+            (EncryptedKeyKind::Uno, UNO_PRIVATE_KEY, UNO_ADDRESS, "UEK"),
+        ] {
+            let encrypted = EncryptedKey::encrypt(kind, &key(private_key), PASSWORD_NFC).unwrap();
+            let encoded = encrypted.to_string();
+            assert_eq!(encoded.len(), 80);
+            assert!(encoded.starts_with(prefix), "{encoded}");
+
+            let parsed: EncryptedKey = encoded.parse().unwrap();
+            assert_eq!(parsed, encrypted);
+            assert_eq!(parsed.kind(), kind);
+            assert_eq!(EncryptedKey::encrypt(kind, &key(private_key), PASSWORD_NFD).unwrap(), encrypted);
+            assert!(parsed.matches_address(address));
+            assert!(!parsed.matches_address(&address.to_lowercase()));
+
+            let entry = parsed.decrypt(PASSWORD_NFD).unwrap();
+            assert_eq!(entry.private_key(), private_key);
+            assert_eq!(entry.address(), address);
+            assert!(matches!(parsed.decrypt("wrong password"), Err(WalletError::WrongPassword)));
+        }
+    }
+
+    #[test]
+    fn mistyped_and_foreign_strings_are_rejected() {
+        let encoded = EncryptedKey::encrypt(EncryptedKeyKind::Tos, &key(TOS_PRIVATE_KEY), "pw")
+            .unwrap()
+            .encode();
+
+        let mut typo = encoded.clone();
+        let last = typo.pop().unwrap();
+        typo.push(if last == '2' { '3' } else { '2' });
+        let foreign = bs58::encode([0u8; PAYLOAD_SIZE]).with_check().into_string();
+        let short = bs58::encode(&[0x0b, 0x43, 0x89][..]).with_check().into_string();
+
+        for bad in [typo, foreign, short, "0OIl".to_string(), String::new()] {
+            assert!(
+                matches!(EncryptedKey::parse(&bad), Err(WalletError::InvalidEncryptedKey(_))),
+                "accepted {bad:?}"
+            );
+        }
+
+        assert_eq!(
+            EncryptedKey::encrypt(EncryptedKeyKind::Uno, &[0xff; 32], "pw"),
+            Err(WalletError::InvalidPrivateKey("UNO key is not a canonical scalar".to_string()))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 // TOS English wordlist for mnemonics (1626 words)
-pub const WORDS: [&str; 1626] = [
+pub static WORDS: [&str; 1626] = [
         "abbey",
         "abducts",
         "ability",
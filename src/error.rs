@@ -0,0 +1,148 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Errors produced while generating or restoring wallet material.
+///
+/// Native callers can match on the variant; at the wasm-bindgen boundary the
+/// error becomes a JS `Error` whose `code` property is [`WalletError::code`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WalletError {
+    /// The recovery phrase failed BIP39 parsing or checksum validation.
+    InvalidMnemonic(String),
+    /// The requested recovery phrase length is not defined by BIP39.
+    InvalidWordCount(usize),
+    /// No BIP39 wordlist exists for the requested language code.
+    UnsupportedLanguage(String),
+    /// The network name is neither `mainnet` nor `testnet`.
+    UnsupportedNetwork(String),
+    /// The BIP32 derivation path string is malformed or out of range.
+    InvalidPath(String),
+    /// An account/address range request is empty, too large or overflows.
+    InvalidRange(String),
+    /// The options object passed from JS could not be decoded.
+    InvalidOptions(String),
+    /// A private key could not be decoded for its curve.
+    InvalidPrivateKey(String),
+    /// A public key is malformed or not a valid point on its curve.
+    InvalidPublicKey(String),
+    /// A serialized BIP32 extended key is malformed or of the wrong kind.
+    InvalidExtendedKey(String),
+    /// A bech32 address has the wrong prefix, checksum, length or type.
+    InvalidAddress(String),
+    /// A mixed-case hex address does not match its Keccak256 checksum.
+    ChecksumMismatch,
+    /// A signature is malformed or no public key can be recovered from it.
+    InvalidSignature(String),
+    /// A keystore MAC or encrypted key tag does not match: the password is
+    /// wrong.
+    WrongPassword,
+    /// A keystore file is malformed, uses an unsupported cipher or KDF, or
+    /// decrypts to a key that does not match its address.
+    CorruptedKeystore(String),
+    /// A password-encrypted private key string is mistyped or malformed.
+    InvalidEncryptedKey(String),
+    /// SLIP-39 or codex32 shares are mistyped, inconsistent or too few to
+    /// recover.
+    InvalidShare(String),
+    /// An unsigned transaction is missing fields or has out-of-range values.
+    InvalidTransaction(String),
+    /// A BIP32 scalar is zero or not below the secp256k1 group order.
+    ScalarOutOfRange,
+    /// A derived private key is zero.
+    ZeroKey,
+    /// The UNO derivation exhausted its counter without finding a usable key.
+    KeyDerivationExhausted,
+    /// The system random number generator failed.
+    Entropy(String),
+    /// Wallet data could not be serialized for JS.
+    Serialization(String),
+}
+
+impl WalletError {
+    /// Stable machine-readable identifier for this error kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidMnemonic(_) => "invalid_mnemonic",
+            Self::InvalidWordCount(_) => "invalid_word_count",
+            Self::UnsupportedLanguage(_) => "unsupported_language",
+            Self::UnsupportedNetwork(_) => "unsupported_network",
+            Self::InvalidPath(_) => "invalid_path",
+            Self::InvalidRange(_) => "invalid_range",
+            Self::InvalidOptions(_) => "invalid_options",
+            Self::InvalidPrivateKey(_) => "invalid_private_key",
+            Self::InvalidPublicKey(_) => "invalid_public_key",
+            Self::InvalidExtendedKey(_) => "invalid_extended_key",
+            Self::InvalidAddress(_) => "invalid_address",
+            Self::ChecksumMismatch => "checksum_mismatch",
+            Self::InvalidSignature(_) => "invalid_signature",
+            Self::WrongPassword => "wrong_password",
+            Self::CorruptedKeystore(_) => "corrupted_keystore",
+            Self::InvalidEncryptedKey(_) => "invalid_encrypted_key",
+            Self::InvalidShare(_) => "invalid_share",
+            Self::InvalidTransaction(_) => "invalid_transaction",
+            Self::ScalarOutOfRange => "scalar_out_of_range",
+            Self::ZeroKey => "zero_key",
+            Self::KeyDerivationExhausted => "key_derivation_exhausted",
+            Self::Entropy(_) => "entropy_unavailable",
+            Self::Serialization(_) => "serialization_failed",
+        }
+    }
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMnemonic(reason) => write!(f, "Invalid mnemonic phrase: {reason}"),
+            Self::InvalidWordCount(count) => {
+                write!(f, "Invalid word count {count}: must be 12, 15, 18, 21 or 24")
+            }
+            Self::UnsupportedLanguage(code) => {
+                write!(f, "Unsupported mnemonic language {code:?}")
+            }
+            Self::UnsupportedNetwork(code) => {
+                write!(f, "Unsupported network {code:?}: must be \"mainnet\" or \"testnet\"")
+            }
+            Self::InvalidPath(reason) => write!(f, "Invalid derivation path: {reason}"),
+            Self::InvalidRange(reason) => write!(f, "Invalid wallet range: {reason}"),
+            Self::InvalidOptions(reason) => write!(f, "Invalid wallet options: {reason}"),
+            Self::InvalidPrivateKey(reason) => write!(f, "Invalid private key: {reason}"),
+            Self::InvalidPublicKey(reason) => write!(f, "Invalid public key: {reason}"),
+            Self::InvalidExtendedKey(reason) => write!(f, "Invalid extended key: {reason}"),
+            Self::InvalidAddress(reason) => write!(f, "Invalid address: {reason}"),
+            Self::ChecksumMismatch => {
+                f.write_str("Address checksum does not match; the address may be mistyped")
+            }
+            Self::InvalidSignature(reason) => write!(f, "Invalid signature: {reason}"),
+            Self::WrongPassword => f.write_str("Wrong password"),
+            Self::CorruptedKeystore(reason) => write!(f, "Corrupted keystore file: {reason}"),
+            Self::InvalidEncryptedKey(reason) => write!(f, "Invalid encrypted private key: {reason}"),
+            Self::InvalidShare(reason) => write!(f, "Invalid recovery share: {reason}"),
+            Self::InvalidTransaction(reason) => write!(f, "Invalid transaction: {reason}"),
+            Self::ScalarOutOfRange => f.write_str("BIP32 scalar is out of range"),
+            Self::ZeroKey => f.write_str("Derived private key is zero"),
+            Self::KeyDerivationExhausted => {
+                f.write_str("Failed to derive a valid UNO private key from mnemonic")
+            }
+            Self::Entropy(reason) => write!(f, "Failed to generate entropy: {reason}"),
+            Self::Serialization(reason) => write!(f, "Failed to serialize wallet data: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+impl From<serde_wasm_bindgen::Error> for WalletError {
+    fn from(err: serde_wasm_bindgen::Error) -> Self {
+        Self::Serialization(err.to_string())
+    }
+}
+
+impl From<WalletError> for JsValue {
+    fn from(err: WalletError) -> Self {
+        let error = js_sys::Error::new(&err.to_string());
+        // Setting a property on a freshly created Error object cannot fail.
+        let _ = js_sys::Reflect::set(&error, &JsValue::from_str("code"), &JsValue::from_str(err.code()));
+        error.into()
+    }
+}
@@ -0,0 +1,146 @@
+//! Mixed-case checksummed `0x` hex addresses used by TOS and UNO wallets.
+
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+
+use crate::WalletError;
+
+const ADDRESS_SIZE: usize = 32;
+
+/// How the letter case of a valid hex address was interpreted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStatus {
+    /// Mixed case matching the Keccak256 checksum.
+    Valid,
+    /// All lowercase or all uppercase, so no checksum is carried.
+    Unchecked,
+}
+
+/// Result of validating a typed or scanned hex address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HexAddressCheck {
+    address: String,
+    checksum: ChecksumStatus,
+}
+
+impl HexAddressCheck {
+    /// Canonical checksummed form of the address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Whether the input carried a checksum.
+    pub fn checksum(&self) -> ChecksumStatus {
+        self.checksum
+    }
+}
+
+/// Encodes `bytes` as `0x` hex with the Keccak256 mixed-case checksum.
+pub fn checksum_hex_address(bytes: &[u8]) -> String {
+    let lower_hex = hex::encode(bytes);
+    let checksum_hash = Keccak256::digest(lower_hex.as_bytes());
+    let mut output = String::with_capacity(lower_hex.len() + 2);
+    output.push_str("0x");
+
+    for (index, ch) in lower_hex.chars().enumerate() {
+        if ch.is_ascii_digit() {
+            output.push(ch);
+            continue;
+        }
+
+        let hash_nibble = if index % 2 == 0 {
+            checksum_hash[index / 2] >> 4
+        } else {
+            checksum_hash[index / 2] & 0x0f
+        };
+
+        if hash_nibble > 7 {
+            output.push(ch.to_ascii_uppercase());
+        } else {
+            output.push(ch);
+        }
+    }
+
+    output
+}
+
+/// Validates a `0x` 32-byte hex address.
+///
+/// Single-case input is accepted as [`ChecksumStatus::Unchecked`]; mixed case
+/// that does not match the checksum means the address was corrupted and is
+/// rejected with [`WalletError::ChecksumMismatch`].
+pub fn validate_hex_address(address: &str) -> Result<HexAddressCheck, WalletError> {
+    let address = address.trim();
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .ok_or_else(|| WalletError::InvalidAddress("hex address must start with 0x".to_string()))?;
+
+    if digits.len() != ADDRESS_SIZE * 2 {
+        return Err(WalletError::InvalidAddress(format!(
+            "hex address has {} digits, expected {}",
+            digits.len(),
+            ADDRESS_SIZE * 2
+        )));
+    }
+
+    let bytes = hex::decode(digits)
+        .map_err(|_| WalletError::InvalidAddress("hex address contains non-hex characters".to_string()))?;
+    let checksummed = checksum_hex_address(&bytes);
+
+    let checksum = if digits == digits.to_ascii_lowercase() || digits == digits.to_ascii_uppercase() {
+        ChecksumStatus::Unchecked
+    } else if checksummed[2..] == *digits {
+        ChecksumStatus::Valid
+    } else {
+        return Err(WalletError::ChecksumMismatch);
+    };
+
+    Ok(HexAddressCheck {
+        address: checksummed,
+        checksum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TOS address of the `abandon … art` test mnemonic.
+    const ADDRESS: &str = "0x9c57F3A17e19682cD9197A1AF278CF59f82Edcf871d630F28EcC8056F25c1CDb";
+
+    #[test]
+    fn checksum_and_case_are_classified() {
+        let valid = validate_hex_address(ADDRESS).unwrap();
+        assert_eq!(valid.checksum(), ChecksumStatus::Valid);
+        assert_eq!(valid.address(), ADDRESS);
+
+        for single_case in [
+            ADDRESS.to_ascii_lowercase(),
+            format!("0x{}", ADDRESS[2..].to_ascii_uppercase()),
+        ] {
+            let unchecked = validate_hex_address(&single_case).unwrap();
+            assert_eq!(unchecked.checksum(), ChecksumStatus::Unchecked);
+            assert_eq!(unchecked.address(), ADDRESS);
+        }
+
+        let corrupted = ADDRESS.replacen('F', "f", 1);
+        assert_eq!(validate_hex_address(&corrupted), Err(WalletError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn malformed_hex_addresses_are_rejected() {
+        for address in [
+            &ADDRESS[2..],
+            &ADDRESS[..ADDRESS.len() - 2],
+            "0x9c57F3A17e19682cD9197A1AF278CF59f82Edcf871d630F28EcC8056F25c1CDg",
+            "",
+        ] {
+            assert!(
+                matches!(validate_hex_address(address), Err(WalletError::InvalidAddress(_))),
+                "accepted {address:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Legacy TOS wallets: a Ristretto key stored as a 25-word seed.
+//!
+//! These predate the BIP39 bundle. The seed encodes the private scalar `s`
+//! directly (see [`crate::mnemonics`]), the public key is `H * s` with
+//! `H = hash_from_bytes::<Sha3_512>("TOS_SIGNATURE_GENERATOR_H")`, and the
+//! address is bech32 `tos1…` (`tst1…` on testnet) over the compressed key plus
+//! an address type byte.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use serde::{Deserialize, Serialize};
+use sha3::Sha3_512;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::address::{Address, AddressType};
+use crate::mnemonics::{scalar_to_words, words_to_scalar};
+use crate::{Network, WalletEntry, WalletError};
+
+const LEGACY_GENERATOR_H_DOMAIN: &[u8] = b"TOS_SIGNATURE_GENERATOR_H";
+
+/// A legacy 25-word TOS seed and the wallet it encodes.
+#[derive(Deserialize, Serialize, Zeroize, ZeroizeOnDrop)]
+pub struct LegacyWallet {
+    mnemonic: String,
+    network: Network,
+    tos: WalletEntry,
+}
+
+impl LegacyWallet {
+    /// Generates a fresh random key and its 25-word seed.
+    pub fn generate(network: Network) -> Result<Self, WalletError> {
+        let mut wide = Zeroizing::new([0u8; 64]);
+        getrandom::getrandom(wide.as_mut_slice()).map_err(|err| WalletError::Entropy(err.to_string()))?;
+        let secret = Zeroizing::new(Scalar::from_bytes_mod_order_wide(&wide));
+        Self::from_secret(&secret, network)
+    }
+
+    /// Restores a wallet from its 25-word seed. The 24-word form without the
+    /// checksum word is also accepted; words are matched case-insensitively.
+    pub fn from_mnemonic(phrase: &str, network: Network) -> Result<Self, WalletError> {
+        let words = Zeroizing::new(
+            phrase
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>(),
+        );
+        let word_refs: Vec<&str> = words.iter().map(String::as_str).collect();
+        let secret = Zeroizing::new(words_to_scalar(&word_refs)?);
+        Self::from_secret(&secret, network)
+    }
+
+    fn from_secret(secret: &Scalar, network: Network) -> Result<Self, WalletError> {
+        if *secret == Scalar::ZERO {
+            return Err(WalletError::ZeroKey);
+        }
+
+        let public_key = (legacy_generator_h() * secret).compress().to_bytes();
+        let mut words = scalar_to_words(secret);
+        let mnemonic = words.join(" ");
+        words.zeroize();
+
+        Ok(Self {
+            mnemonic,
+            network,
+            tos: WalletEntry {
+                label: network.label("TOS"),
+                signer_type: "schnorr".to_string(),
+                address: Address::new(network, public_key, AddressType::Normal)?.encode(),
+                public_key: hex::encode(public_key),
+                private_key: hex::encode(Zeroizing::new(secret.to_bytes()).as_slice()),
+            },
+        })
+    }
+
+    /// Canonical 25-word seed, lowercase and single-spaced.
+    pub fn mnemonic(&self) -> &str {
+        &self.mnemonic
+    }
+
+    /// Network the address was encoded for.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Legacy TOS wallet: compressed Ristretto public key, bech32 `tos1…` address.
+    pub fn tos(&self) -> &WalletEntry {
+        &self.tos
+    }
+}
+
+pub(crate) fn legacy_generator_h() -> RistrettoPoint {
+    RistrettoPoint::hash_from_bytes::<Sha3_512>(LEGACY_GENERATOR_H_DOMAIN)
+}
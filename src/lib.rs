@@ -1,35 +1,87 @@
-use bip39::{Language, Mnemonic};
+//! TOS Network paper wallet derivation.
+//!
+//! The crate builds both the WASM module used by `web/` and an `rlib` that
+//! native services can link to reproduce exactly the same wallet material:
+//!
+//! ```
+//! use tos_paper_wallet::{DerivationPath, WalletBundle};
+//!
+//! let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon \
+//!               abandon abandon abandon abandon abandon abandon abandon abandon \
+//!               abandon abandon abandon abandon abandon abandon abandon art";
+//! let bundle = WalletBundle::from_mnemonic(phrase, "", &DerivationPath::default()).unwrap();
+//! assert_eq!(bundle.tos().signer_type(), "secp256k1");
+//! assert_eq!(bundle.uno().signer_type(), "elgamal");
+//! ```
+
+use bip39::Mnemonic;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT,
     ristretto::RistrettoPoint,
     scalar::Scalar,
 };
 use hmac::{Hmac, Mac};
-use k256::{
-    ecdsa::SigningKey,
-};
-use num_bigint::BigUint;
-use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 use sha2::Sha512;
 use sha3::{Digest, Keccak256, Sha3_512};
 use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+mod address;
+mod bip32;
+mod codex32;
+mod encrypted_key;
+mod english_words;
+mod error;
+mod hex_address;
+mod keystore;
+mod legacy;
+mod message;
+mod mnemonics;
+mod network;
+mod schnorr;
+mod slip39;
+mod slip39_words;
+mod transaction;
+
+pub use address::{Address, AddressType, DataElement, DataValue, MAX_INTEGRATED_DATA_SIZE};
+pub use bip32::{DerivationPath, ExtendedPrivateKey, ExtendedPublicKey, HD_HARDENED_OFFSET};
+pub use bip39::Language;
+pub use codex32::{combine_codex32, split_codex32, Codex32Share, MAX_CODEX32_SHARE_COUNT, MAX_CODEX32_THRESHOLD};
+pub use encrypted_key::{EncryptedKey, EncryptedKeyKind};
+pub use error::WalletError;
+pub use hex_address::{checksum_hex_address, validate_hex_address, ChecksumStatus, HexAddressCheck};
+pub use keystore::{
+    Keystore, KeystoreKdf, DEFAULT_PBKDF2_ITERATIONS, LIGHT_SCRYPT_N, LIGHT_SCRYPT_P, SCRYPT_R, STANDARD_SCRYPT_N,
+    STANDARD_SCRYPT_P,
+};
+pub use legacy::LegacyWallet;
+pub use message::{
+    personal_message_hash, recover_personal_message_address, sign_personal_message, verify_personal_message,
+    SIGNATURE_SIZE,
+};
+pub use network::Network;
+pub use schnorr::{sign_schnorr, verify_schnorr, SchnorrScheme, SCHNORR_SIGNATURE_SIZE};
+pub use slip39::{combine_slip39, split_slip39, Slip39Group, MAX_SLIP39_SHARE_COUNT, SLIP39_ITERATION_EXPONENT};
+pub use transaction::{AccessListItem, Quantity, SignedTransaction, UnsignedTransaction};
+
+use bip32::secp256k1_public_key;
 
 type HmacSha512 = Hmac<Sha512>;
 
-const DEFAULT_HD_PATH: &str = "m/44'/60'/0'/0/0";
-const HD_HARDENED_OFFSET: u32 = 0x8000_0000;
-const DEFAULT_DERIVATION_PATH: [u32; 5] = [
-    HD_HARDENED_OFFSET + 44,
-    HD_HARDENED_OFFSET + 60,
-    HD_HARDENED_OFFSET,
-    0,
-    0,
-];
-const SECP256K1_ORDER_HEX: &str =
-    "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141";
+/// HD path used when no other path is selected.
+pub const DEFAULT_HD_PATH: &str = "m/44'/60'/0'/0/0";
+const BIP44_PURPOSE: u32 = 44;
+const BIP44_COIN_TYPE: u32 = 60;
+const BIP44_ACCOUNT_DEPTH: usize = 3;
+const MAX_RANGE_COUNT: u32 = 100;
+/// Recovery phrase length used when no other length is selected.
+pub const DEFAULT_WORD_COUNT: usize = 24;
+/// Recovery phrase lengths defined by BIP39.
+pub const SUPPORTED_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
 
-#[derive(Deserialize, Serialize)]
+/// Key material and address for one signer type.
+#[derive(Deserialize, Serialize, Zeroize, ZeroizeOnDrop)]
 pub struct WalletEntry {
     label: String,
     signer_type: String,
@@ -38,241 +90,1243 @@ pub struct WalletEntry {
     private_key: String,
 }
 
-#[derive(Deserialize, Serialize)]
+impl WalletEntry {
+    /// Display label, `TOS` or `UNO`.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// GTOS signer type, `secp256k1` or `elgamal`.
+    pub fn signer_type(&self) -> &str {
+        &self.signer_type
+    }
+
+    /// Mixed-case checksummed `0x` address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Lowercase hex public key.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// Lowercase hex private key.
+    pub fn private_key(&self) -> &str {
+        &self.private_key
+    }
+}
+
+/// Recovery phrase plus the TOS and UNO wallets derived from it.
+#[derive(Deserialize, Serialize, Zeroize, ZeroizeOnDrop)]
 pub struct WalletBundle {
     mnemonic: String,
+    language: String,
+    word_count: usize,
+    network: Network,
     hd_path: String,
+    passphrase_protected: bool,
+    account_path: Option<String>,
+    account_xpub: Option<String>,
+    account_xprv: Option<String>,
+    slip39_shares: Option<Vec<Vec<String>>>,
     tos: WalletEntry,
     uno: WalletEntry,
 }
 
-#[wasm_bindgen]
-pub fn generate_wallet_bundle() -> Result<JsValue, JsValue> {
-    let mnemonic = generate_mnemonic()?;
-    let bundle = derive_bundle_from_phrase(mnemonic.to_string().as_str())?;
-    serde_wasm_bindgen::to_value(&bundle)
-        .map_err(|err| JsValue::from_str(&format!("Failed to serialize wallet bundle: {err}")))
+impl WalletBundle {
+    /// Generates a fresh 24-word recovery phrase and derives its mainnet wallets.
+    pub fn generate(passphrase: &str, path: &DerivationPath) -> Result<Self, WalletError> {
+        Self::generate_with_word_count(DEFAULT_WORD_COUNT, passphrase, path)
+    }
+
+    /// Generates an English recovery phrase of `word_count` words (12, 15,
+    /// 18, 21 or 24) and derives its mainnet wallets.
+    pub fn generate_with_word_count(
+        word_count: usize,
+        passphrase: &str,
+        path: &DerivationPath,
+    ) -> Result<Self, WalletError> {
+        Self::generate_in(Language::English, word_count, passphrase, path, Network::Mainnet)
+    }
+
+    /// Generates a recovery phrase from the `language` BIP39 wordlist and
+    /// derives its wallets for `network`.
+    pub fn generate_in(
+        language: Language,
+        word_count: usize,
+        passphrase: &str,
+        path: &DerivationPath,
+        network: Network,
+    ) -> Result<Self, WalletError> {
+        let mnemonic = generate_mnemonic(language, word_count)?;
+        derive_bundle_from_mnemonic(&mnemonic, passphrase, path, network)
+    }
+
+    /// Restores the mainnet wallets for an existing recovery phrase, detecting
+    /// its wordlist language.
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        path: &DerivationPath,
+    ) -> Result<Self, WalletError> {
+        derive_bundle_from_phrase(phrase, passphrase, path)
+    }
+
+    /// Restores the `network` wallets for a recovery phrase in `language`,
+    /// or in the detected language when `None`.
+    pub fn from_mnemonic_in(
+        language: Option<Language>,
+        phrase: &str,
+        passphrase: &str,
+        path: &DerivationPath,
+        network: Network,
+    ) -> Result<Self, WalletError> {
+        let mnemonic = parse_mnemonic_in(phrase, language)?;
+        derive_bundle_from_mnemonic(&mnemonic, passphrase, path, network)
+    }
+
+    /// Restores the wallets from SLIP-39 shares made by
+    /// [`WalletBundle::with_slip39_shares`]. The recovered entropy is turned
+    /// back into a `language` recovery phrase; `slip39_passphrase` unlocks the
+    /// shares and `passphrase` is the BIP39 passphrase as usual.
+    pub fn from_slip39_shares<S: AsRef<str>>(
+        shares: &[S],
+        slip39_passphrase: &str,
+        language: Language,
+        passphrase: &str,
+        path: &DerivationPath,
+        network: Network,
+    ) -> Result<Self, WalletError> {
+        let entropy = combine_slip39(shares, slip39_passphrase)?;
+        validate_bip39_entropy_len(entropy.len())?;
+        let mnemonic =
+            Mnemonic::from_entropy_in(language, &entropy).map_err(|err| WalletError::InvalidShare(err.to_string()))?;
+        derive_bundle_from_mnemonic(&mnemonic, passphrase, path, network)
+    }
+
+    /// Adds SLIP-39 shares of the recovery phrase's entropy: any
+    /// `group_threshold` of `groups`, each with its member threshold of
+    /// shares, restore the bundle. `slip39_passphrase` may be empty and must
+    /// be printable ASCII.
+    pub fn with_slip39_shares(
+        mut self,
+        group_threshold: u8,
+        groups: &[Slip39Group],
+        slip39_passphrase: &str,
+    ) -> Result<Self, WalletError> {
+        let mnemonic = parse_mnemonic_in(&self.mnemonic, Some(language_from_code(&self.language)?))?;
+        let (entropy, len) = mnemonic.to_entropy_array();
+        let entropy = Zeroizing::new(entropy);
+        self.slip39_shares = Some(split_slip39(&entropy[..len], group_threshold, groups, slip39_passphrase)?);
+        Ok(self)
+    }
+
+    /// Adds the BIP32 `xprv` of the account node, which is as sensitive as
+    /// the recovery phrase and left out unless asked for. `passphrase` is the
+    /// BIP39 passphrase the bundle was derived with; a different one fails
+    /// with [`WalletError::InvalidOptions`].
+    pub fn with_account_xprv(mut self, passphrase: &str) -> Result<Self, WalletError> {
+        let Some(account_path) = &self.account_path else {
+            return Ok(self);
+        };
+        let mnemonic = parse_mnemonic_in(&self.mnemonic, Some(language_from_code(&self.language)?))?;
+        let seed = seed_from_mnemonic(&mnemonic, passphrase);
+        let node = ExtendedPrivateKey::derive(&seed, &DerivationPath::parse(account_path)?)?;
+        if self.account_xpub.as_deref() != Some(node.extended_public_key()?.to_xpub().as_str()) {
+            return Err(WalletError::InvalidOptions(
+                "passphrase does not match the one the bundle was derived with".to_string(),
+            ));
+        }
+        self.account_xprv = Some(node.to_xprv());
+        Ok(self)
+    }
+
+    /// Normalized recovery phrase. Japanese phrases are separated by
+    /// ideographic spaces (U+3000), all others by ASCII spaces.
+    pub fn mnemonic(&self) -> &str {
+        &self.mnemonic
+    }
+
+    /// Wordlist language code, e.g. `en`, `ja` or `zh-hans`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Number of words in the recovery phrase.
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    /// Entropy strength of the recovery phrase in bits (128 to 256).
+    pub fn entropy_bits(&self) -> usize {
+        self.word_count / 3 * 32
+    }
+
+    /// Network the wallets were generated for.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Canonical HD path the wallets were derived at.
+    pub fn hd_path(&self) -> &str {
+        &self.hd_path
+    }
+
+    /// Whether a non-empty BIP39 passphrase was used.
+    pub fn passphrase_protected(&self) -> bool {
+        self.passphrase_protected
+    }
+
+    /// Account-level node of `hd_path` (its first three segments, e.g.
+    /// `m/44'/60'/0'`), or `None` when the path is shorter than that.
+    pub fn account_path(&self) -> Option<&str> {
+        self.account_path.as_deref()
+    }
+
+    /// BIP32 `xpub` of the account node, for watch-only wallets.
+    pub fn account_xpub(&self) -> Option<&str> {
+        self.account_xpub.as_deref()
+    }
+
+    /// BIP32 `xprv` of the account node, when added with
+    /// [`WalletBundle::with_account_xprv`].
+    pub fn account_xprv(&self) -> Option<&str> {
+        self.account_xprv.as_deref()
+    }
+
+    /// SLIP-39 shares of the recovery phrase, one list of mnemonics per
+    /// group, when requested.
+    pub fn slip39_shares(&self) -> Option<&[Vec<String>]> {
+        self.slip39_shares.as_deref()
+    }
+
+    /// TOS (`secp256k1`) wallet.
+    pub fn tos(&self) -> &WalletEntry {
+        &self.tos
+    }
+
+    /// UNO (`elgamal`) wallet.
+    pub fn uno(&self) -> &WalletEntry {
+        &self.uno
+    }
+}
+
+/// Optional settings accepted by `generate_wallet_bundle_with_options`.
+///
+/// Missing fields fall back to the defaults used by `generate_wallet_bundle`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct BundleOptions {
+    hd_path: Option<String>,
+    passphrase: Zeroizing<String>,
+    word_count: Option<usize>,
+    language: Option<String>,
+    network: Option<String>,
+    slip39: Option<Slip39Options>,
+    include_account_xprv: bool,
 }
 
-fn generate_mnemonic() -> Result<Mnemonic, JsValue> {
-    let mut entropy = [0u8; 32];
-    getrandom::getrandom(&mut entropy)
-        .map_err(|err| JsValue::from_str(&format!("Failed to generate entropy: {err}")))?;
+/// SLIP-39 settings inside the bundle options, e.g.
+/// `{ group_threshold: 2, groups: [[1, 1], [2, 3], [3, 5]], passphrase: "..." }`.
+///
+/// Each group is `[member_threshold, member_count]`. `group_threshold`
+/// defaults to 1 and `passphrase` to empty.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Slip39Options {
+    group_threshold: Option<u8>,
+    groups: Vec<(u8, u8)>,
+    passphrase: Zeroizing<String>,
+}
 
-    Mnemonic::from_entropy_in(Language::English, &entropy)
-        .map_err(|err| JsValue::from_str(&format!("Failed to build mnemonic: {err}")))
+impl Slip39Options {
+    fn groups(&self) -> Vec<Slip39Group> {
+        self.groups
+            .iter()
+            .map(|&(member_threshold, member_count)| Slip39Group::new(member_threshold, member_count))
+            .collect()
+    }
 }
 
-fn derive_bundle_from_phrase(phrase: &str) -> Result<WalletBundle, JsValue> {
-    let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase)
-        .map_err(|err| JsValue::from_str(&format!("Invalid mnemonic phrase: {err}")))?;
-    let seed = mnemonic.to_seed_normalized("");
-    let tos = derive_tos_wallet(&seed)?;
-    let uno = derive_uno_wallet(&seed)?;
+impl BundleOptions {
+    fn derivation_path(&self) -> Result<DerivationPath, WalletError> {
+        DerivationPath::parse(self.hd_path.as_deref().unwrap_or(DEFAULT_HD_PATH))
+    }
 
-    Ok(WalletBundle {
-        mnemonic: mnemonic.to_string(),
-        hd_path: DEFAULT_HD_PATH.to_string(),
-        tos,
-        uno,
-    })
+    fn language(&self) -> Result<Option<Language>, WalletError> {
+        self.language.as_deref().map(language_from_code).transpose()
+    }
+
+    fn network(&self) -> Result<Network, WalletError> {
+        parse_network(self.network.as_deref())
+    }
+
+    fn account_xprv(&self, bundle: WalletBundle) -> Result<WalletBundle, WalletError> {
+        if self.include_account_xprv {
+            bundle.with_account_xprv(&self.passphrase)
+        } else {
+            Ok(bundle)
+        }
+    }
 }
 
-fn derive_tos_wallet(seed: &[u8]) -> Result<WalletEntry, JsValue> {
-    let private_key = derive_ecdsa_private_from_seed(seed)?;
-    let public_key = secp256k1_public_key(&private_key, false)?;
-    let address_hash = Keccak256::digest(&public_key[1..]);
+/// Optional settings accepted by `export_keystore`.
+///
+/// `kdf` is `"scrypt"` (the default) or `"pbkdf2"`; missing costs fall back to
+/// geth's standard scrypt parameters or [`DEFAULT_PBKDF2_ITERATIONS`].
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct KeystoreOptions {
+    kdf: Option<String>,
+    scrypt_n: Option<u32>,
+    scrypt_r: Option<u32>,
+    scrypt_p: Option<u32>,
+    pbkdf2_iterations: Option<u32>,
+}
 
-    Ok(WalletEntry {
-        label: "TOS".to_string(),
-        signer_type: "secp256k1".to_string(),
-        address: checksum_hex_address(address_hash.as_slice()),
-        public_key: hex::encode(public_key),
-        private_key: hex::encode(private_key),
-    })
+impl KeystoreOptions {
+    fn kdf(&self) -> Result<KeystoreKdf, WalletError> {
+        match self.kdf.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("scrypt") => Ok(KeystoreKdf::Scrypt {
+                n: self.scrypt_n.unwrap_or(STANDARD_SCRYPT_N),
+                r: self.scrypt_r.unwrap_or(SCRYPT_R),
+                p: self.scrypt_p.unwrap_or(STANDARD_SCRYPT_P),
+            }),
+            Some("pbkdf2") => Ok(KeystoreKdf::Pbkdf2 {
+                iterations: self.pbkdf2_iterations.unwrap_or(DEFAULT_PBKDF2_ITERATIONS),
+            }),
+            Some(other) => Err(WalletError::InvalidOptions(format!(
+                "unknown keystore kdf {other:?}: must be \"scrypt\" or \"pbkdf2\""
+            ))),
+        }
+    }
 }
 
-fn derive_uno_wallet(seed: &[u8]) -> Result<WalletEntry, JsValue> {
-    let private_key = derive_elgamal_private_from_seed(seed)?;
-    let secret = Scalar::from_canonical_bytes(private_key)
-        .into_option()
-        .ok_or_else(|| JsValue::from_str("Failed to decode UNO private key"))?;
+/// Maps a language code (`en`, `es`, `fr`, `it`, `pt`, `cs`, `ja`, `ko`,
+/// `zh`/`zh-hans`, `zh-hant`) to its BIP39 wordlist.
+pub fn language_from_code(code: &str) -> Result<Language, WalletError> {
+    match code.to_ascii_lowercase().replace('_', "-").as_str() {
+        "en" => Ok(Language::English),
+        "zh" | "zh-hans" | "zh-cn" => Ok(Language::SimplifiedChinese),
+        "zh-hant" | "zh-tw" => Ok(Language::TraditionalChinese),
+        "cs" => Ok(Language::Czech),
+        "fr" => Ok(Language::French),
+        "it" => Ok(Language::Italian),
+        "ja" => Ok(Language::Japanese),
+        "ko" => Ok(Language::Korean),
+        "pt" => Ok(Language::Portuguese),
+        "es" => Ok(Language::Spanish),
+        _ => Err(WalletError::UnsupportedLanguage(code.to_string())),
+    }
+}
 
-    if secret == Scalar::ZERO {
-        return Err(JsValue::from_str("Derived UNO private key cannot be zero"));
+/// Canonical code for a BIP39 wordlist, the inverse of [`language_from_code`].
+pub fn language_code(language: Language) -> &'static str {
+    match language {
+        Language::English => "en",
+        Language::SimplifiedChinese => "zh-hans",
+        Language::TraditionalChinese => "zh-hant",
+        Language::Czech => "cs",
+        Language::French => "fr",
+        Language::Italian => "it",
+        Language::Japanese => "ja",
+        Language::Korean => "ko",
+        Language::Portuguese => "pt",
+        Language::Spanish => "es",
     }
+}
 
-    let generator = elgamal_generator_h();
-    let public_point = generator * secret.invert();
-    let public_key = public_point.compress().to_bytes();
-    let address_hash = Keccak256::digest(public_key);
+/// One TOS + UNO wallet pair derived at a single HD path.
+#[derive(Deserialize, Serialize, Zeroize, ZeroizeOnDrop)]
+pub struct WalletPair {
+    network: Network,
+    hd_path: String,
+    tos: WalletEntry,
+    uno: WalletEntry,
+}
 
-    Ok(WalletEntry {
-        label: "UNO".to_string(),
-        signer_type: "elgamal".to_string(),
-        address: checksum_hex_address(address_hash.as_slice()),
-        public_key: hex::encode(public_key),
-        private_key: hex::encode(private_key),
+impl WalletPair {
+    /// Derives `count` consecutive pairs for `network` starting at index `start`.
+    pub fn range_from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        index_kind: RangeIndex,
+        start: u32,
+        count: u32,
+        network: Network,
+    ) -> Result<Vec<Self>, WalletError> {
+        derive_range_from_phrase(phrase, passphrase, index_kind, start, count, network)
+    }
+
+    /// Network the pair was generated for.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Canonical HD path of this pair.
+    pub fn hd_path(&self) -> &str {
+        &self.hd_path
+    }
+
+    /// TOS (`secp256k1`) wallet.
+    pub fn tos(&self) -> &WalletEntry {
+        &self.tos
+    }
+
+    /// UNO (`elgamal`) wallet.
+    pub fn uno(&self) -> &WalletEntry {
+        &self.uno
+    }
+}
+
+/// TOS address derived from an account `xpub` without any private key.
+#[derive(Deserialize, Serialize)]
+pub struct WatchOnlyAddress {
+    index: u32,
+    address: String,
+    public_key: String,
+}
+
+impl WatchOnlyAddress {
+    /// Derives `count` receive addresses `<account>/0/i` from an account
+    /// `xpub`, starting at `i = start`.
+    pub fn range_from_xpub(xpub: &str, start: u32, count: u32) -> Result<Vec<Self>, WalletError> {
+        derive_watch_only_range(&ExtendedPublicKey::parse(xpub)?, start, count)
+    }
+
+    /// Address index below the external chain.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Mixed-case checksummed `0x` TOS address.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Lowercase hex uncompressed secp256k1 public key.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+}
+
+/// Which BIP44 index a wallet range enumerates.
+///
+/// `Account` walks `m/44'/60'/i'/0/0`, `Address` walks `m/44'/60'/0'/0/i`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeIndex {
+    Account,
+    Address,
+}
+
+impl RangeIndex {
+    /// Parses `"account"` or `"address"`.
+    pub fn parse(value: &str) -> Result<Self, WalletError> {
+        match value {
+            "account" => Ok(Self::Account),
+            "address" => Ok(Self::Address),
+            other => Err(WalletError::InvalidRange(format!(
+                "index kind {other:?} must be \"account\" or \"address\""
+            ))),
+        }
+    }
+
+    /// BIP44 path for `index` along this dimension.
+    pub fn path(self, index: u32) -> DerivationPath {
+        let (account, address_index) = match self {
+            Self::Account => (index, 0),
+            Self::Address => (0, index),
+        };
+
+        DerivationPath {
+            indices: vec![
+                HD_HARDENED_OFFSET + BIP44_PURPOSE,
+                HD_HARDENED_OFFSET + BIP44_COIN_TYPE,
+                HD_HARDENED_OFFSET + account,
+                0,
+                address_index,
+            ],
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn generate_wallet_bundle() -> Result<JsValue, JsValue> {
+    generate_bundle(&BundleOptions::default())
+}
+
+#[wasm_bindgen]
+pub fn generate_wallet_bundle_with_path(hd_path: &str) -> Result<JsValue, JsValue> {
+    generate_bundle(&BundleOptions {
+        hd_path: Some(hd_path.to_string()),
+        ..BundleOptions::default()
     })
 }
 
-fn derive_ecdsa_private_from_seed(seed: &[u8]) -> Result<[u8; 32], JsValue> {
-    let (mut key, mut chain_code) = derive_bip32_master(seed)?;
+/// Generates a bundle from an options object such as
+/// `{ hd_path: "m/44'/60'/0'/0/0", passphrase: "...", word_count: 12, language: "ja", network: "testnet" }`;
+/// every field is optional. A `slip39` entry (see `Slip39Options`) adds
+/// SLIP-39 shares of the recovery phrase to the bundle.
+#[wasm_bindgen]
+pub fn generate_wallet_bundle_with_options(options: JsValue) -> Result<JsValue, JsValue> {
+    generate_bundle(&parse_bundle_options(options)?)
+}
+
+/// Re-derives the bundle for a recovery phrase typed in by the user.
+///
+/// Accepts the same options object as `generate_wallet_bundle_with_options`.
+/// The wordlist language is detected unless `language` is given. The phrase
+/// is matched case-insensitively and extra whitespace is ignored; unknown
+/// words and checksum failures are reported with an `invalid_mnemonic`
+/// error code.
+#[wasm_bindgen]
+pub fn restore_wallet_bundle(phrase: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let options = parse_bundle_options(options)?;
+    let path = options.derivation_path()?;
+    let mnemonic = parse_mnemonic_in(phrase, options.language()?)?;
+    let bundle = derive_bundle_from_mnemonic(&mnemonic, &options.passphrase, &path, options.network()?)?;
+    let bundle = options.account_xprv(bundle)?;
+    Ok(serde_wasm_bindgen::to_value(&bundle).map_err(WalletError::from)?)
+}
+
+/// Combines SLIP-39 shares (an array of mnemonics) from a bundle generated
+/// with a `slip39` option and re-derives that bundle.
+///
+/// Accepts the same options object as `generate_wallet_bundle_with_options`;
+/// `slip39.passphrase` unlocks the shares and `language` (English by default)
+/// selects the wordlist of the restored recovery phrase.
+#[wasm_bindgen]
+pub fn combine_slip39_shares(shares: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let shares: Vec<Zeroizing<String>> =
+        serde_wasm_bindgen::from_value(shares).map_err(|err| WalletError::InvalidShare(err.to_string()))?;
+    let options = parse_bundle_options(options)?;
+    let slip39_passphrase = options.slip39.as_ref().map_or("", |slip39| slip39.passphrase.as_str());
+    let bundle = WalletBundle::from_slip39_shares(
+        &shares,
+        slip39_passphrase,
+        options.language()?.unwrap_or(Language::English),
+        &options.passphrase,
+        &options.derivation_path()?,
+        options.network()?,
+    )?;
+    let bundle = options.account_xprv(bundle)?;
+    Ok(serde_wasm_bindgen::to_value(&bundle).map_err(WalletError::from)?)
+}
 
-    for index in DEFAULT_DERIVATION_PATH {
-        let (child_key, child_chain_code) = derive_bip32_child(&key, &chain_code, index)?;
-        key = child_key;
-        chain_code = child_chain_code;
+/// Encodes a recovery phrase as codex32 (BIP93) strings: `count` shares of
+/// which any `threshold` (2 to 9) recover it, or a single unshared string for
+/// 1-of-1. The 4-character `identifier` is random unless given.
+#[wasm_bindgen]
+pub fn split_phrase_codex32(
+    phrase: &str,
+    threshold: u8,
+    count: u8,
+    identifier: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let shares = phrase_to_codex32(phrase, threshold, count, identifier.as_deref())?
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    Ok(serde_wasm_bindgen::to_value(&shares).map_err(WalletError::from)?)
+}
+
+/// Recovers the recovery phrase from an array of codex32 strings, in the
+/// `language` wordlist (English by default). The result can be passed to
+/// `restore_wallet_bundle`.
+#[wasm_bindgen]
+pub fn combine_codex32_shares(shares: JsValue, language: Option<String>) -> Result<String, JsValue> {
+    let shares: Vec<Zeroizing<String>> =
+        serde_wasm_bindgen::from_value(shares).map_err(|err| WalletError::InvalidShare(err.to_string()))?;
+    let language = language.as_deref().map(language_from_code).transpose()?.unwrap_or(Language::English);
+    Ok(codex32_to_phrase(&shares, language)?.to_string())
+}
+
+/// Header fields of a codex32 string as returned to JS.
+#[derive(Serialize)]
+struct Codex32Info {
+    threshold: u8,
+    identifier: String,
+    share_index: char,
+}
+
+/// Checks the checksum of one hand-copied codex32 string and returns its
+/// `{ threshold, identifier, share_index }` without recovering anything.
+#[wasm_bindgen]
+pub fn decode_codex32_share(share: &str) -> Result<JsValue, JsValue> {
+    let share = Codex32Share::parse(share)?;
+    let info = Codex32Info {
+        threshold: share.threshold(),
+        identifier: share.identifier(),
+        share_index: share.share_index(),
+    };
+    Ok(serde_wasm_bindgen::to_value(&info).map_err(WalletError::from)?)
+}
+
+fn generate_bundle(options: &BundleOptions) -> Result<JsValue, JsValue> {
+    let path = options.derivation_path()?;
+    let word_count = options.word_count.unwrap_or(DEFAULT_WORD_COUNT);
+    let language = options.language()?.unwrap_or(Language::English);
+    let bundle = WalletBundle::generate_in(
+        language,
+        word_count,
+        &options.passphrase,
+        &path,
+        options.network()?,
+    )?;
+    let bundle = match &options.slip39 {
+        Some(slip39) => {
+            bundle.with_slip39_shares(slip39.group_threshold.unwrap_or(1), &slip39.groups(), &slip39.passphrase)?
+        }
+        None => bundle,
+    };
+    let bundle = options.account_xprv(bundle)?;
+    Ok(serde_wasm_bindgen::to_value(&bundle).map_err(WalletError::from)?)
+}
+
+fn parse_bundle_options(options: JsValue) -> Result<BundleOptions, WalletError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(BundleOptions::default());
     }
 
-    Ok(key)
+    serde_wasm_bindgen::from_value(options).map_err(|err| WalletError::InvalidOptions(err.to_string()))
 }
 
-fn derive_elgamal_private_from_seed(seed: &[u8]) -> Result<[u8; 32], JsValue> {
-    for counter in 0..1024u32 {
-        let mut mac = HmacSha512::new_from_slice(b"GTOS_ELGAMAL_DERIVE")
-            .map_err(|err| JsValue::from_str(&format!("Failed to initialize UNO derivation: {err}")))?;
-        mac.update(seed);
-        mac.update(&[0]);
-        mac.update(DEFAULT_HD_PATH.as_bytes());
-        mac.update(&counter.to_be_bytes());
+/// Parses an optional network name from JS; missing means mainnet.
+fn parse_network(network: Option<&str>) -> Result<Network, WalletError> {
+    network.map(Network::parse).transpose().map(Option::unwrap_or_default)
+}
 
-        let digest = mac.finalize().into_bytes();
-        let mut wide = [0u8; 64];
-        wide.copy_from_slice(&digest);
+/// Derives `count` TOS + UNO pairs from one recovery phrase, starting at
+/// `start` for the account or address index selected by `index_kind`.
+/// `network` is `"mainnet"` (the default) or `"testnet"`.
+#[wasm_bindgen]
+pub fn derive_wallet_range(
+    phrase: &str,
+    passphrase: &str,
+    index_kind: &str,
+    start: u32,
+    count: u32,
+    network: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let index_kind = RangeIndex::parse(index_kind)?;
+    let network = parse_network(network.as_deref())?;
+    let pairs = derive_range_from_phrase(phrase, passphrase, index_kind, start, count, network)?;
+    Ok(serde_wasm_bindgen::to_value(&pairs).map_err(WalletError::from)?)
+}
 
-        let scalar = Scalar::from_bytes_mod_order_wide(&wide);
-        if scalar == Scalar::ZERO {
-            continue;
+/// Derives `count` watch-only TOS addresses from an account `xpub` such as
+/// the bundle's `account_xpub`, walking `<account>/0/i` from `i = start`.
+#[wasm_bindgen]
+pub fn derive_watch_only_addresses(xpub: &str, start: u32, count: u32) -> Result<JsValue, JsValue> {
+    let addresses = WatchOnlyAddress::range_from_xpub(xpub, start, count)?;
+    Ok(serde_wasm_bindgen::to_value(&addresses).map_err(WalletError::from)?)
+}
+
+/// Generates a legacy TOS wallet: a random Ristretto key as a 25-word seed
+/// with its `H * s` public key and bech32 `tos1…` (or testnet `tst1…`) address.
+#[wasm_bindgen]
+pub fn generate_legacy_wallet(network: Option<String>) -> Result<JsValue, JsValue> {
+    let wallet = LegacyWallet::generate(parse_network(network.as_deref())?)?;
+    Ok(serde_wasm_bindgen::to_value(&wallet).map_err(WalletError::from)?)
+}
+
+/// Restores a legacy TOS wallet from its 25-word seed.
+#[wasm_bindgen]
+pub fn restore_legacy_wallet(phrase: &str, network: Option<String>) -> Result<JsValue, JsValue> {
+    let wallet = LegacyWallet::from_mnemonic(phrase, parse_network(network.as_deref())?)?;
+    Ok(serde_wasm_bindgen::to_value(&wallet).map_err(WalletError::from)?)
+}
+
+/// Fields of a decoded bech32 address as returned to JS.
+#[derive(Serialize)]
+struct AddressInfo {
+    address: String,
+    network: Network,
+    public_key: String,
+    address_type: &'static str,
+    data: Option<String>,
+    normal_address: String,
+}
+
+impl From<&Address> for AddressInfo {
+    fn from(address: &Address) -> Self {
+        Self {
+            address: address.encode(),
+            network: address.network(),
+            public_key: hex::encode(address.public_key()),
+            address_type: address.address_type().code(),
+            data: address.data_bytes().map(hex::encode),
+            normal_address: address.to_normal().encode(),
         }
+    }
+}
+
+/// Encodes a hex compressed Ristretto public key as a normal `tos1…`
+/// address, or `tst1…` when `network` is `"testnet"`.
+#[wasm_bindgen]
+pub fn encode_address(public_key: &str, network: Option<String>) -> Result<String, JsValue> {
+    let address = Address::new(
+        parse_network(network.as_deref())?,
+        parse_ristretto_public_key(public_key)?,
+        AddressType::Normal,
+    )?;
+    Ok(address.encode())
+}
+
+/// Encodes an integrated address carrying `data`, the hex of a serialized
+/// TOS data element of up to 1024 bytes, after the public key.
+#[wasm_bindgen]
+pub fn encode_integrated_address(
+    public_key: &str,
+    data: &str,
+    network: Option<String>,
+) -> Result<String, JsValue> {
+    let data = hex::decode(data.trim())
+        .map_err(|_| WalletError::InvalidAddress("integrated address data must be hex".to_string()))?;
+    let address = Address::new(
+        parse_network(network.as_deref())?,
+        parse_ristretto_public_key(public_key)?,
+        AddressType::Data(DataElement::from_bytes(&data)?),
+    )?;
+    Ok(address.encode())
+}
+
+fn parse_ristretto_public_key(public_key: &str) -> Result<[u8; 32], WalletError> {
+    hex::decode(public_key.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| WalletError::InvalidPublicKey("public key must be 32 bytes of hex".to_string()))
+}
+
+/// Decodes a 32-byte hex private key, with or without `0x`.
+pub(crate) fn parse_private_key_hex(private_key: &str) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+    let private_key = private_key.trim();
+    let digits = private_key.strip_prefix("0x").unwrap_or(private_key);
+    let bytes = Zeroizing::new(
+        hex::decode(digits).map_err(|_| WalletError::InvalidPrivateKey("private key must be hex".to_string()))?,
+    );
 
-        return Ok(scalar.to_bytes());
+    let mut output = Zeroizing::new([0u8; 32]);
+    if bytes.len() != output.len() {
+        return Err(WalletError::InvalidPrivateKey(format!(
+            "private key is {} bytes, expected 32",
+            bytes.len()
+        )));
     }
+    output.copy_from_slice(&bytes);
+    Ok(output)
+}
 
-    Err(JsValue::from_str(
-        "Failed to derive a valid UNO private key from mnemonic",
-    ))
+/// Decodes a `tos1…` or `tst1…` address into `{ address, network,
+/// public_key, address_type, data, normal_address }`; `data` is the hex
+/// serialized data element of an integrated address and `null` otherwise.
+#[wasm_bindgen]
+pub fn decode_address(address: &str) -> Result<JsValue, JsValue> {
+    let info = AddressInfo::from(&Address::decode(address)?);
+    Ok(serde_wasm_bindgen::to_value(&info).map_err(WalletError::from)?)
 }
 
-fn derive_bip32_master(seed: &[u8]) -> Result<([u8; 32], [u8; 32]), JsValue> {
-    let mut mac = HmacSha512::new_from_slice(b"Bitcoin seed")
-        .map_err(|err| JsValue::from_str(&format!("Failed to initialize BIP32 master derivation: {err}")))?;
-    mac.update(seed);
-    let digest = mac.finalize().into_bytes();
+/// Validates a typed or scanned `0x` hex address and returns
+/// `{ address, checksum: "valid" | "unchecked" }`. Mixed case that fails the
+/// checksum throws with the code `checksum_mismatch`.
+#[wasm_bindgen]
+pub fn validate_address(address: &str) -> Result<JsValue, JsValue> {
+    let check = validate_hex_address(address)?;
+    Ok(serde_wasm_bindgen::to_value(&check).map_err(WalletError::from)?)
+}
 
-    let mut key = [0u8; 32];
-    let mut chain_code = [0u8; 32];
-    key.copy_from_slice(&digest[..32]);
-    chain_code.copy_from_slice(&digest[32..]);
-    validate_bip32_scalar(&key)?;
+/// Signs a UTF-8 `message` EIP-191 `personal_sign` style with a hex TOS
+/// private key and returns the 65-byte `r || s || v` signature as `0x` hex.
+#[wasm_bindgen]
+pub fn sign_message(private_key: &str, message: &str) -> Result<String, JsValue> {
+    let private_key = parse_private_key_hex(private_key)?;
+    let signature = sign_personal_message(&private_key, message.as_bytes())?;
+    Ok(format!("0x{}", hex::encode(signature)))
+}
 
-    Ok((key, chain_code))
+/// Recovers the checksummed TOS address that produced a `sign_message`
+/// signature.
+#[wasm_bindgen]
+pub fn recover_message_signer(message: &str, signature: &str) -> Result<String, JsValue> {
+    Ok(recover_personal_message_address(message.as_bytes(), &parse_signature(signature)?)?)
 }
 
-fn derive_bip32_child(
-    parent_key: &[u8; 32],
-    parent_chain_code: &[u8; 32],
-    index: u32,
-) -> Result<([u8; 32], [u8; 32]), JsValue> {
-    let mut data = [0u8; 37];
+/// Returns whether `signature` over `message` was made by the key behind
+/// the hex `address`. Malformed input throws `invalid_signature` or
+/// `invalid_address`.
+#[wasm_bindgen]
+pub fn verify_message(address: &str, message: &str, signature: &str) -> Result<bool, JsValue> {
+    Ok(verify_personal_message(address, message.as_bytes(), &parse_signature(signature)?)?)
+}
+
+/// Signs an unsigned GTOS transaction given as JSON with a hex TOS private
+/// key and returns `{ kind, from, hash, raw }`, where `raw` is the `0x` hex
+/// transaction to broadcast from an online machine. `network` picks the
+/// chain ID and defaults to `"mainnet"`.
+#[wasm_bindgen]
+pub fn sign_transaction(private_key: &str, transaction: &str, network: Option<String>) -> Result<JsValue, JsValue> {
+    let private_key = parse_private_key_hex(private_key)?;
+    let network = parse_network(network.as_deref())?;
+    let signed = UnsignedTransaction::from_json(transaction, network)?.sign(&private_key)?;
+    Ok(serde_wasm_bindgen::to_value(&signed).map_err(WalletError::from)?)
+}
 
-    if index >= HD_HARDENED_OFFSET {
-        data[1..33].copy_from_slice(parent_key);
+/// Encrypts a hex TOS private key into keystore v3 JSON, ready to save as a
+/// file. `options` is `{ kdf, scrypt_n, scrypt_r, scrypt_p,
+/// pbkdf2_iterations }`; every field is optional.
+#[wasm_bindgen]
+pub fn export_keystore(private_key: &str, password: &str, options: JsValue) -> Result<String, JsValue> {
+    let options: KeystoreOptions = if options.is_undefined() || options.is_null() {
+        KeystoreOptions::default()
     } else {
-        let compressed_public_key = secp256k1_public_key(parent_key, true)?;
-        data[..33].copy_from_slice(&compressed_public_key);
-    }
+        serde_wasm_bindgen::from_value(options).map_err(|err| WalletError::InvalidOptions(err.to_string()))?
+    };
+    let private_key = parse_private_key_hex(private_key)?;
+    Ok(Keystore::encrypt(&private_key, password, options.kdf()?)?.to_json())
+}
 
-    data[33..].copy_from_slice(&index.to_be_bytes());
+/// Encrypts a hex TOS (`kind = "tos"`) or UNO (`kind = "uno"`) private key
+/// under `password` and returns the 80-character `TEK…` / `UEK…` string to
+/// print instead of the raw key.
+#[wasm_bindgen]
+pub fn encrypt_private_key(kind: &str, private_key: &str, password: &str) -> Result<String, JsValue> {
+    let kind = EncryptedKeyKind::parse(kind)?;
+    let private_key = parse_private_key_hex(private_key)?;
+    Ok(EncryptedKey::encrypt(kind, &private_key, password)?.encode())
+}
 
-    let mut mac = HmacSha512::new_from_slice(parent_chain_code)
-        .map_err(|err| JsValue::from_str(&format!("Failed to initialize BIP32 child derivation: {err}")))?;
-    mac.update(&data);
-    let digest = mac.finalize().into_bytes();
+/// Decrypts a `TEK…` / `UEK…` string and returns the wallet entry of its key.
+/// Throws `wrong_password` or, for a mistyped string, `invalid_encrypted_key`.
+#[wasm_bindgen]
+pub fn decrypt_private_key(encrypted: &str, password: &str) -> Result<JsValue, JsValue> {
+    let entry = EncryptedKey::parse(encrypted)?.decrypt(password)?;
+    Ok(serde_wasm_bindgen::to_value(&entry).map_err(WalletError::from)?)
+}
 
-    let mut child_chain_code = [0u8; 32];
-    child_chain_code.copy_from_slice(&digest[32..]);
+/// Decrypts keystore v3 JSON with `password` and returns the TOS wallet
+/// entry of its key, for printing a paper backup. Throws `wrong_password`
+/// when the MAC does not match and `corrupted_keystore` for a damaged or
+/// unsupported file.
+#[wasm_bindgen]
+pub fn import_keystore(json: &str, password: &str) -> Result<JsValue, JsValue> {
+    let entry = Keystore::from_json(json)?.decrypt(password)?;
+    Ok(serde_wasm_bindgen::to_value(&entry).map_err(WalletError::from)?)
+}
 
-    let parent_value = BigUint::from_bytes_be(parent_key);
-    let child_scalar = BigUint::from_bytes_be(&digest[..32]);
-    let curve_order = secp256k1_order();
+/// Signs a UTF-8 `message` with a hex UNO (`scheme = "uno"`) or legacy TOS
+/// (`scheme = "legacy"`) private key and returns the 64-byte `s || e`
+/// Schnorr signature as hex.
+#[wasm_bindgen]
+pub fn sign_schnorr_message(scheme: &str, private_key: &str, message: &str) -> Result<String, JsValue> {
+    let scheme = SchnorrScheme::parse(scheme)?;
+    let private_key = parse_private_key_hex(private_key)?;
+    Ok(hex::encode(sign_schnorr(scheme, &private_key, message.as_bytes())?))
+}
+
+/// Returns whether a `sign_schnorr_message` signature over `message` matches
+/// the hex compressed Ristretto `public_key`.
+#[wasm_bindgen]
+pub fn verify_schnorr_message(
+    scheme: &str,
+    public_key: &str,
+    message: &str,
+    signature: &str,
+) -> Result<bool, JsValue> {
+    let scheme = SchnorrScheme::parse(scheme)?;
+    let public_key = parse_ristretto_public_key(public_key)?;
+    Ok(verify_schnorr(scheme, &public_key, message.as_bytes(), &parse_signature(signature)?)?)
+}
 
-    if child_scalar.is_zero() || child_scalar >= curve_order {
-        return Err(JsValue::from_str("Invalid BIP32 child scalar"));
+fn parse_signature(signature: &str) -> Result<Vec<u8>, WalletError> {
+    let signature = signature.trim();
+    hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .map_err(|_| WalletError::InvalidSignature("signature must be hex".to_string()))
+}
+
+fn generate_mnemonic(language: Language, word_count: usize) -> Result<Mnemonic, WalletError> {
+    if !SUPPORTED_WORD_COUNTS.contains(&word_count) {
+        return Err(WalletError::InvalidWordCount(word_count));
     }
 
-    let derived_value = (child_scalar + parent_value) % &curve_order;
-    if derived_value.is_zero() {
-        return Err(JsValue::from_str("Derived BIP32 child key is zero"));
+    // Every 3 words carry 32 bits of entropy plus 1 checksum bit.
+    let mut entropy = Zeroizing::new([0u8; 32]);
+    let entropy = &mut entropy[..word_count / 3 * 4];
+    getrandom::getrandom(entropy).map_err(|err| WalletError::Entropy(err.to_string()))?;
+
+    Mnemonic::from_entropy_in(language, entropy)
+        .map_err(|err| WalletError::Entropy(err.to_string()))
+}
+
+fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, WalletError> {
+    parse_mnemonic_in(phrase, None)
+}
+
+/// Parses a user-typed phrase in `language`, or in the detected language when
+/// `None`. Input is NFKD-normalized, lowercased and whitespace-collapsed first.
+fn parse_mnemonic_in(phrase: &str, language: Option<Language>) -> Result<Mnemonic, WalletError> {
+    let mut normalized = std::borrow::Cow::Borrowed(phrase);
+    Mnemonic::normalize_utf8_cow(&mut normalized);
+    let words = Zeroizing::new(
+        normalized
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>(),
+    );
+    if let std::borrow::Cow::Owned(owned) = &mut normalized {
+        owned.zeroize();
     }
+    let normalized = Zeroizing::new(words.join(" "));
 
-    let mut child_key = [0u8; 32];
-    let child_bytes = derived_value.to_bytes_be();
-    child_key[32 - child_bytes.len()..].copy_from_slice(&child_bytes);
-    validate_bip32_scalar(&child_key)?;
+    let language = match language {
+        Some(language) => language,
+        None => detect_language(&normalized)?,
+    };
 
-    Ok((child_key, child_chain_code))
+    Mnemonic::parse_in_normalized(language, &normalized).map_err(|err| {
+        let reason = match err {
+            bip39::Error::UnknownWord(index) => format!(
+                "word {} ({:?}) is not in the BIP39 {language} wordlist",
+                index + 1,
+                normalized.split(' ').nth(index).unwrap_or_default()
+            ),
+            bip39::Error::InvalidChecksum => {
+                "checksum does not match; check the spelling and order of the words".to_string()
+            }
+            other => other.to_string(),
+        };
+        WalletError::InvalidMnemonic(reason)
+    })
 }
 
-fn validate_bip32_scalar(key: &[u8; 32]) -> Result<(), JsValue> {
-    let scalar = BigUint::from_bytes_be(key);
-    let curve_order = secp256k1_order();
+/// Detects the wordlist of a normalized phrase.
+///
+/// The simplified and traditional Chinese lists share characters, so a phrase
+/// may fit both. The seed depends only on the words themselves, so any
+/// candidate whose checksum validates yields the same wallets; the first one
+/// is reported. When no list fits, the one knowing the most words is returned
+/// so that parsing reports the mistake against the intended language.
+fn detect_language(normalized: &str) -> Result<Language, WalletError> {
+    let candidates: Vec<Language> = match Mnemonic::language_of(normalized) {
+        Ok(language) => return Ok(language),
+        Err(bip39::Error::AmbiguousLanguages(ambiguous)) => ambiguous.iter().collect(),
+        Err(bip39::Error::UnknownWord(_)) => Language::ALL.to_vec(),
+        Err(err) => return Err(WalletError::InvalidMnemonic(err.to_string())),
+    };
+
+    if let Some(language) = candidates
+        .iter()
+        .copied()
+        .find(|language| Mnemonic::parse_in_normalized(*language, normalized).is_ok())
+    {
+        return Ok(language);
+    }
+
+    let known_words = |language: &Language| {
+        normalized
+            .split(' ')
+            .filter(|word| language.find_word(word).is_some())
+            .count()
+    };
+    let most_known = candidates.iter().map(known_words).max().unwrap_or_default();
+
+    Ok(candidates
+        .into_iter()
+        .find(|language| known_words(language) == most_known)
+        .unwrap_or(Language::English))
+}
+
+/// Display form of a phrase; BIP39 separates Japanese words with U+3000.
+fn display_phrase(mnemonic: &Mnemonic) -> String {
+    let separator = if mnemonic.language() == Language::Japanese {
+        "\u{3000}"
+    } else {
+        " "
+    };
+    mnemonic.words().collect::<Vec<_>>().join(separator)
+}
+
+/// 64-byte BIP39 seed, wiped when dropped. Dereferences to `[u8]`.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Seed([u8; 64]);
+
+impl std::ops::Deref for Seed {
+    type Target = [u8];
 
-    if scalar.is_zero() || scalar >= curve_order {
-        return Err(JsValue::from_str("BIP32 scalar is out of range"));
+    fn deref(&self) -> &[u8] {
+        &self.0
     }
+}
+
+impl AsRef<[u8]> for Seed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
 
+/// Validates `phrase` and returns its 64-byte BIP39 seed, which is the input
+/// to [`derive_tos_wallet`] and [`derive_uno_wallet`].
+pub fn seed_from_phrase(phrase: &str, passphrase: &str) -> Result<Seed, WalletError> {
+    let mnemonic = parse_mnemonic(phrase)?;
+    Ok(seed_from_mnemonic(&mnemonic, passphrase))
+}
+
+/// Encodes the entropy of `phrase` as codex32 strings: `count` shares of
+/// which any `threshold` recover it, or the unshared secret for 1-of-1.
+pub fn phrase_to_codex32(
+    phrase: &str,
+    threshold: u8,
+    count: u8,
+    identifier: Option<&str>,
+) -> Result<Vec<Codex32Share>, WalletError> {
+    let mnemonic = parse_mnemonic(phrase)?;
+    let (entropy, len) = mnemonic.to_entropy_array();
+    let entropy = Zeroizing::new(entropy);
+    split_codex32(&entropy[..len], threshold, count, identifier)
+}
+
+/// Recovers the `language` recovery phrase from codex32 strings made by
+/// [`phrase_to_codex32`], in the display form [`WalletBundle::mnemonic`] uses.
+pub fn codex32_to_phrase<S: AsRef<str>>(shares: &[S], language: Language) -> Result<Zeroizing<String>, WalletError> {
+    let entropy = combine_codex32(shares)?;
+    validate_bip39_entropy_len(entropy.len())?;
+    let mnemonic =
+        Mnemonic::from_entropy_in(language, &entropy).map_err(|err| WalletError::InvalidShare(err.to_string()))?;
+    Ok(Zeroizing::new(display_phrase(&mnemonic)))
+}
+
+/// Checks that a secret recovered from shares is BIP39 entropy: 16 to 32
+/// bytes in steps of 4.
+fn validate_bip39_entropy_len(len: usize) -> Result<(), WalletError> {
+    if !(16..=32).contains(&len) || !len.is_multiple_of(4) {
+        return Err(WalletError::InvalidShare(format!("shares hold a {len}-byte secret, not BIP39 entropy")));
+    }
     Ok(())
 }
 
-fn secp256k1_public_key(private_key: &[u8; 32], compressed: bool) -> Result<Vec<u8>, JsValue> {
-    let signing_key = SigningKey::from_bytes(private_key.into())
-        .map_err(|err| JsValue::from_str(&format!("Invalid secp256k1 private key: {err}")))?;
-    let encoded_point = signing_key.verifying_key().to_encoded_point(compressed);
-    Ok(encoded_point.as_bytes().to_vec())
+/// Builds the BIP39 seed. `to_seed` NFKD-normalizes the passphrase as BIP39
+/// requires; for ASCII passphrases this equals `bip39.NewSeed(mnemonic, passphrase)`.
+fn seed_from_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Seed {
+    Seed(mnemonic.to_seed(passphrase))
 }
 
-fn secp256k1_order() -> BigUint {
-    BigUint::parse_bytes(SECP256K1_ORDER_HEX.as_bytes(), 16)
-        .expect("valid secp256k1 curve order")
+fn derive_bundle_from_phrase(
+    phrase: &str,
+    passphrase: &str,
+    path: &DerivationPath,
+) -> Result<WalletBundle, WalletError> {
+    let mnemonic = parse_mnemonic(phrase)?;
+    derive_bundle_from_mnemonic(&mnemonic, passphrase, path, Network::Mainnet)
 }
 
-fn elgamal_generator_h() -> RistrettoPoint {
-    let base = RISTRETTO_BASEPOINT_POINT.compress().to_bytes();
-    let digest = Sha3_512::digest(base);
-    let mut uniform = [0u8; 64];
-    uniform.copy_from_slice(&digest);
-    RistrettoPoint::from_uniform_bytes(&uniform)
+fn derive_bundle_from_mnemonic(
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    path: &DerivationPath,
+    network: Network,
+) -> Result<WalletBundle, WalletError> {
+    let seed = seed_from_mnemonic(mnemonic, passphrase);
+    let tos = label_for_network(derive_tos_wallet(&seed, path)?, network);
+    let uno = label_for_network(derive_uno_wallet(&seed, path)?, network);
+    let account = path
+        .truncate(BIP44_ACCOUNT_DEPTH)
+        .map(|account_path| {
+            let node = ExtendedPrivateKey::derive(&seed, &account_path)?;
+            Ok::<_, WalletError>((account_path, node.extended_public_key()?))
+        })
+        .transpose()?;
+
+    Ok(WalletBundle {
+        mnemonic: display_phrase(mnemonic),
+        language: language_code(mnemonic.language()).to_string(),
+        word_count: mnemonic.word_count(),
+        network,
+        hd_path: path.to_string(),
+        passphrase_protected: !passphrase.is_empty(),
+        account_path: account.as_ref().map(|(path, _)| path.to_string()),
+        account_xpub: account.as_ref().map(|(_, xpub)| xpub.to_xpub()),
+        account_xprv: None,
+        slip39_shares: None,
+        tos,
+        uno,
+    })
 }
 
-fn checksum_hex_address(bytes: &[u8]) -> String {
-    let lower_hex = hex::encode(bytes);
-    let checksum_hash = Keccak256::digest(lower_hex.as_bytes());
-    let mut output = String::with_capacity(lower_hex.len() + 2);
-    output.push_str("0x");
+fn derive_range_from_phrase(
+    phrase: &str,
+    passphrase: &str,
+    index_kind: RangeIndex,
+    start: u32,
+    count: u32,
+    network: Network,
+) -> Result<Vec<WalletPair>, WalletError> {
+    let indices = range_indices(start, count)?;
+    let mnemonic = parse_mnemonic(phrase)?;
+    let seed = seed_from_mnemonic(&mnemonic, passphrase);
 
-    for (index, ch) in lower_hex.chars().enumerate() {
-        if ch.is_ascii_digit() {
-            output.push(ch);
-            continue;
-        }
+    indices
+        .map(|index| {
+            let path = index_kind.path(index);
+            Ok(WalletPair {
+                network,
+                hd_path: path.to_string(),
+                tos: label_for_network(derive_tos_wallet(&seed, &path)?, network),
+                uno: label_for_network(derive_uno_wallet(&seed, &path)?, network),
+            })
+        })
+        .collect()
+}
 
-        let hash_nibble = if index % 2 == 0 {
-            checksum_hash[index / 2] >> 4
-        } else {
-            checksum_hash[index / 2] & 0x0f
-        };
+fn range_indices(start: u32, count: u32) -> Result<std::ops::Range<u32>, WalletError> {
+    if count == 0 || count > MAX_RANGE_COUNT {
+        return Err(WalletError::InvalidRange(format!(
+            "count {count} must be between 1 and {MAX_RANGE_COUNT}"
+        )));
+    }
 
-        if hash_nibble > 7 {
-            output.push(ch.to_ascii_uppercase());
-        } else {
-            output.push(ch);
+    match start.checked_add(count) {
+        Some(end) if end <= HD_HARDENED_OFFSET => Ok(start..end),
+        _ => Err(WalletError::InvalidRange(format!(
+            "start {start} plus count {count} must stay below {HD_HARDENED_OFFSET}"
+        ))),
+    }
+}
+
+fn derive_watch_only_range(
+    account: &ExtendedPublicKey,
+    start: u32,
+    count: u32,
+) -> Result<Vec<WatchOnlyAddress>, WalletError> {
+    let indices = range_indices(start, count)?;
+    let external_chain = account.derive_child(0)?;
+
+    indices
+        .map(|index| {
+            let public_key = external_chain.derive_child(index)?.uncompressed_public_key()?;
+            Ok(WatchOnlyAddress {
+                index,
+                address: tos_address(&public_key),
+                public_key: hex::encode(public_key),
+            })
+        })
+        .collect()
+}
+
+/// Derives the TOS wallet: BIP32 secp256k1 key at `path`, Keccak256 address.
+pub fn derive_tos_wallet(seed: &[u8], path: &DerivationPath) -> Result<WalletEntry, WalletError> {
+    let private_key = derive_ecdsa_private_from_seed(seed, path)?;
+    tos_wallet_entry(&private_key)
+}
+
+/// TOS wallet entry for a secp256k1 private key.
+pub(crate) fn tos_wallet_entry(private_key: &[u8; 32]) -> Result<WalletEntry, WalletError> {
+    let public_key = secp256k1_public_key(private_key, false)?;
+
+    Ok(WalletEntry {
+        label: "TOS".to_string(),
+        signer_type: "secp256k1".to_string(),
+        address: tos_address(&public_key),
+        public_key: hex::encode(public_key),
+        private_key: hex::encode(private_key),
+    })
+}
+
+/// Derives the UNO wallet: `GTOS_ELGAMAL_DERIVE` scalar keyed by the
+/// canonical `path` string, public key `H * s^-1`.
+pub fn derive_uno_wallet(seed: &[u8], path: &DerivationPath) -> Result<WalletEntry, WalletError> {
+    let private_key = derive_elgamal_private_from_seed(seed, path)?;
+    uno_wallet_entry(&private_key)
+}
+
+/// UNO wallet entry for a canonical Ristretto private scalar.
+pub(crate) fn uno_wallet_entry(private_key: &[u8; 32]) -> Result<WalletEntry, WalletError> {
+    let secret = Scalar::from_canonical_bytes(*private_key)
+        .into_option()
+        .map(Zeroizing::new)
+        .ok_or_else(|| WalletError::InvalidPrivateKey("UNO key is not a canonical scalar".to_string()))?;
+
+    if *secret == Scalar::ZERO {
+        return Err(WalletError::ZeroKey);
+    }
+
+    let generator = elgamal_generator_h();
+    let public_point = generator * *Zeroizing::new(secret.invert());
+    let public_key = public_point.compress().to_bytes();
+    let address_hash = Keccak256::digest(public_key);
+
+    Ok(WalletEntry {
+        label: "UNO".to_string(),
+        signer_type: "elgamal".to_string(),
+        address: checksum_hex_address(address_hash.as_slice()),
+        public_key: hex::encode(public_key),
+        private_key: hex::encode(private_key),
+    })
+}
+
+/// Marks an entry's label with its network, e.g. `TOS Testnet`.
+fn label_for_network(mut entry: WalletEntry, network: Network) -> WalletEntry {
+    entry.label = network.label(&entry.label);
+    entry
+}
+
+/// TOS address of an uncompressed secp256k1 public key: `Keccak256(pubkey[1..])`.
+pub(crate) fn tos_address(uncompressed_public_key: &[u8]) -> String {
+    checksum_hex_address(&Keccak256::digest(&uncompressed_public_key[1..]))
+}
+
+fn derive_ecdsa_private_from_seed(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+    Ok(Zeroizing::new(*ExtendedPrivateKey::derive(seed, path)?.private_key()))
+}
+
+fn derive_elgamal_private_from_seed(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+    let hd_path = path.to_string();
+
+    for counter in 0..1024u32 {
+        let mut mac = new_hmac_sha512(b"GTOS_ELGAMAL_DERIVE");
+        mac.update(seed);
+        mac.update(&[0]);
+        mac.update(hd_path.as_bytes());
+        mac.update(&counter.to_be_bytes());
+
+        let wide = finalize_hmac_sha512(mac);
+        let scalar = Zeroizing::new(Scalar::from_bytes_mod_order_wide(&wide));
+        if *scalar == Scalar::ZERO {
+            continue;
         }
+
+        return Ok(Zeroizing::new(scalar.to_bytes()));
     }
 
+    Err(WalletError::KeyDerivationExhausted)
+}
+
+pub(crate) fn new_hmac_sha512(key: &[u8]) -> HmacSha512 {
+    HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+/// Finishes `mac` into a buffer that is wiped on drop.
+pub(crate) fn finalize_hmac_sha512(mac: HmacSha512) -> Zeroizing<[u8; 64]> {
+    let mut digest = mac.finalize().into_bytes();
+    let mut output = Zeroizing::new([0u8; 64]);
+    output.copy_from_slice(&digest);
+    digest.as_mut_slice().zeroize();
     output
 }
 
+pub(crate) fn elgamal_generator_h() -> RistrettoPoint {
+    let base = RISTRETTO_BASEPOINT_POINT.compress().to_bytes();
+    let digest = Sha3_512::digest(base);
+    let mut uniform = [0u8; 64];
+    uniform.copy_from_slice(&digest);
+    RistrettoPoint::from_uniform_bytes(&uniform)
+}
+
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
@@ -284,10 +1338,12 @@ mod tests {
 
     #[test]
     fn generated_bundle_has_expected_shape() {
-        let mnemonic = generate_mnemonic().unwrap();
-        let parsed = derive_bundle_from_phrase(&mnemonic.to_string()).unwrap();
+        let mnemonic = generate_mnemonic(Language::English, DEFAULT_WORD_COUNT).unwrap();
+        let path = DerivationPath::parse(DEFAULT_HD_PATH).unwrap();
+        let parsed = derive_bundle_from_phrase(&mnemonic.to_string(), "", &path).unwrap();
 
         assert_eq!(parsed.mnemonic.split_whitespace().count(), 24);
+        assert_eq!(parsed.word_count, 24);
         assert_eq!(parsed.hd_path, DEFAULT_HD_PATH);
         assert_eq!(parsed.tos.private_key.len(), 64);
         assert_eq!(parsed.uno.private_key.len(), 64);
@@ -302,13 +1358,265 @@ mod tests {
     #[test]
     fn known_mnemonic_derives_stable_wallets() {
         let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
-        let bundle = derive_bundle_from_phrase(phrase).unwrap();
+        let path = DerivationPath::parse(DEFAULT_HD_PATH).unwrap();
+        let bundle = derive_bundle_from_phrase(phrase, "", &path).unwrap();
 
         assert_eq!(bundle.mnemonic, phrase);
+        assert_eq!(bundle.language, "en");
+        assert!(!bundle.passphrase_protected);
         assert_eq!(bundle.tos.signer_type, "secp256k1");
         assert_eq!(bundle.uno.signer_type, "elgamal");
         assert_ne!(bundle.tos.address, bundle.uno.address);
         assert_ne!(bundle.tos.public_key, bundle.uno.public_key);
         assert_ne!(bundle.tos.private_key, bundle.uno.private_key);
     }
+
+    #[test]
+    fn custom_path_is_threaded_into_bundle() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+        let default_path = DerivationPath::parse(DEFAULT_HD_PATH).unwrap();
+        let custom_path = DerivationPath::parse("m/44h/60h/3h/0/7").unwrap();
+
+        let default_bundle = derive_bundle_from_phrase(phrase, "", &default_path).unwrap();
+        let custom_bundle = derive_bundle_from_phrase(phrase, "", &custom_path).unwrap();
+
+        assert_eq!(custom_bundle.hd_path, "m/44'/60'/3'/0/7");
+        assert_ne!(custom_bundle.tos.private_key, default_bundle.tos.private_key);
+        assert_ne!(custom_bundle.uno.private_key, default_bundle.uno.private_key);
+    }
+
+    #[test]
+    fn address_range_matches_single_derivations() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+        let pairs = derive_range_from_phrase(phrase, "", RangeIndex::Address, 0, 3, Network::Mainnet).unwrap();
+
+        assert_eq!(pairs.len(), 3);
+        for (index, pair) in pairs.iter().enumerate() {
+            let expected_path = format!("m/44'/60'/0'/0/{index}");
+            let path = DerivationPath::parse(&expected_path).unwrap();
+            let bundle = derive_bundle_from_phrase(phrase, "", &path).unwrap();
+
+            assert_eq!(pair.hd_path, expected_path);
+            assert_eq!(pair.tos.address, bundle.tos.address);
+            assert_eq!(pair.uno.address, bundle.uno.address);
+        }
+
+        let accounts = derive_range_from_phrase(phrase, "", RangeIndex::Account, 5, 2, Network::Mainnet).unwrap();
+        assert_eq!(accounts[0].hd_path, "m/44'/60'/5'/0/0");
+        assert_eq!(accounts[1].hd_path, "m/44'/60'/6'/0/0");
+    }
+
+    #[test]
+    fn range_bounds_are_validated() {
+        for (start, count) in [
+            (0, 0),
+            (0, MAX_RANGE_COUNT + 1),
+            (HD_HARDENED_OFFSET - 1, 2),
+            (u32::MAX, 1),
+        ] {
+            assert!(matches!(
+                range_indices(start, count),
+                Err(WalletError::InvalidRange(_))
+            ));
+        }
+        assert_eq!(range_indices(HD_HARDENED_OFFSET - 1, 1).unwrap().count(), 1);
+        assert!(matches!(
+            RangeIndex::parse("change"),
+            Err(WalletError::InvalidRange(_))
+        ));
+    }
+
+    #[test]
+    fn passphrase_seed_matches_bip39_vector() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+        let mnemonic = parse_mnemonic(phrase).unwrap();
+
+        assert_eq!(
+            hex::encode(seed_from_mnemonic(&mnemonic, "TREZOR")),
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8"
+        );
+        assert_eq!(
+            &*seed_from_mnemonic(&mnemonic, "caf\u{00e9}"),
+            &*seed_from_mnemonic(&mnemonic, "cafe\u{0301}")
+        );
+    }
+
+    #[test]
+    fn passphrase_changes_wallets_and_is_not_stored() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+        let path = DerivationPath::parse(DEFAULT_HD_PATH).unwrap();
+        let plain = derive_bundle_from_phrase(phrase, "", &path).unwrap();
+        let protected = derive_bundle_from_phrase(phrase, "TREZOR", &path).unwrap();
+
+        assert!(protected.passphrase_protected);
+        assert_eq!(protected.mnemonic, plain.mnemonic);
+        assert_ne!(protected.tos.address, plain.tos.address);
+        assert_ne!(protected.uno.address, plain.uno.address);
+    }
+
+    #[test]
+    fn invalid_mnemonic_is_reported_as_typed_error() {
+        let path = DerivationPath::parse(DEFAULT_HD_PATH).unwrap();
+        let bad_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+
+        let err = derive_bundle_from_phrase(bad_checksum, "", &path).err().unwrap();
+        assert!(matches!(err, WalletError::InvalidMnemonic(_)));
+        assert_eq!(err.code(), "invalid_mnemonic");
+        assert!(err.to_string().starts_with("Invalid mnemonic phrase: "));
+    }
+
+    #[test]
+    fn restore_normalizes_case_and_whitespace() {
+        let path = DerivationPath::default();
+        let typed = "  Abandon abandon ABANDON abandon abandon abandon abandon abandon\n abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art ";
+        let canonical = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+        let restored = WalletBundle::from_mnemonic(typed, "", &path).unwrap();
+        let expected = WalletBundle::from_mnemonic(canonical, "", &path).unwrap();
+
+        assert_eq!(restored.mnemonic, canonical);
+        assert_eq!(restored.tos.address, expected.tos.address);
+        assert_eq!(restored.uno.address, expected.uno.address);
+    }
+
+    #[test]
+    fn restore_reports_which_word_is_wrong() {
+        let unknown = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandonn abandon art";
+        let err = parse_mnemonic(unknown).err().unwrap();
+        assert_eq!(
+            err,
+            WalletError::InvalidMnemonic(
+                "word 22 (\"abandonn\") is not in the BIP39 English wordlist".to_string()
+            )
+        );
+
+        let short = "abandon abandon abandon";
+        assert!(parse_mnemonic(short).err().unwrap().to_string().contains("word count"));
+    }
+
+    #[test]
+    fn generation_supports_every_bip39_length() {
+        let path = DerivationPath::default();
+
+        for word_count in SUPPORTED_WORD_COUNTS {
+            let bundle = WalletBundle::generate_with_word_count(word_count, "", &path).unwrap();
+            assert_eq!(bundle.mnemonic.split_whitespace().count(), word_count);
+            assert_eq!(bundle.word_count(), word_count);
+
+            let restored = WalletBundle::from_mnemonic(&bundle.mnemonic, "", &path).unwrap();
+            assert_eq!(restored.tos.address, bundle.tos.address);
+            assert_eq!(restored.word_count(), word_count);
+        }
+
+        for word_count in [0, 11, 13, 25] {
+            assert_eq!(
+                generate_mnemonic(Language::English, word_count).err(),
+                Some(WalletError::InvalidWordCount(word_count))
+            );
+        }
+    }
+
+    #[test]
+    fn twelve_word_phrase_restores_with_bip39_seed() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let bundle = WalletBundle::from_mnemonic(phrase, "", &DerivationPath::default()).unwrap();
+
+        assert_eq!(bundle.word_count(), 12);
+        assert_eq!(bundle.entropy_bits(), 128);
+        assert_eq!(
+            hex::encode(seed_from_phrase(phrase, "TREZOR").unwrap()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    fn non_english_phrases_round_trip_with_detection() {
+        let path = DerivationPath::default();
+
+        for language in Language::ALL.iter().copied() {
+            let bundle = WalletBundle::generate_in(language, 12, "", &path, Network::Mainnet).unwrap();
+            assert_eq!(bundle.language(), language_code(language));
+
+            let restored = WalletBundle::from_mnemonic(bundle.mnemonic(), "", &path).unwrap();
+            assert_eq!(restored.mnemonic, bundle.mnemonic);
+            assert_eq!(restored.tos.address, bundle.tos.address);
+            assert_eq!(restored.uno.address, bundle.uno.address);
+        }
+    }
+
+    #[test]
+    fn japanese_phrase_uses_ideographic_spaces() {
+        let path = DerivationPath::default();
+        let bundle = WalletBundle::generate_in(Language::Japanese, 12, "", &path, Network::Mainnet).unwrap();
+
+        assert_eq!(bundle.mnemonic.split('\u{3000}').count(), 12);
+        assert!(!bundle.mnemonic.contains(' '));
+
+        let ascii_spaced = bundle.mnemonic.replace('\u{3000}', " ");
+        let restored = WalletBundle::from_mnemonic_in(Some(Language::Japanese), &ascii_spaced, "", &path, Network::Mainnet).unwrap();
+        assert_eq!(restored.tos.address, bundle.tos.address);
+    }
+
+    #[test]
+    fn language_codes_round_trip() {
+        for language in Language::ALL.iter().copied() {
+            assert_eq!(language_from_code(language_code(language)).unwrap(), language);
+        }
+        assert_eq!(language_from_code("zh").unwrap(), Language::SimplifiedChinese);
+        assert_eq!(
+            language_from_code("de"),
+            Err(WalletError::UnsupportedLanguage("de".to_string()))
+        );
+    }
+
+    #[test]
+    fn secret_material_is_wiped_on_zeroize() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<Seed>();
+        assert_zeroize_on_drop::<WalletEntry>();
+        assert_zeroize_on_drop::<WalletBundle>();
+        assert_zeroize_on_drop::<WalletPair>();
+        assert_zeroize_on_drop::<ExtendedPrivateKey>();
+        assert_zeroize_on_drop::<Mnemonic>();
+
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut seed = seed_from_phrase(phrase, "").unwrap();
+        assert!(seed.iter().any(|byte| *byte != 0));
+        seed.zeroize();
+        assert!(seed.iter().all(|byte| *byte == 0));
+
+        let mut bundle = WalletBundle::from_mnemonic(phrase, "", &DerivationPath::default())
+            .unwrap()
+            .with_account_xprv("")
+            .unwrap();
+        assert!(bundle.account_xprv.is_some());
+        bundle.zeroize();
+        assert!(bundle.mnemonic.is_empty());
+        assert!(bundle.tos.private_key.is_empty());
+        assert!(bundle.uno.private_key.is_empty());
+        assert_eq!(bundle.account_xprv, None);
+
+        let mut wide = finalize_hmac_sha512(new_hmac_sha512(b"key"));
+        wide.zeroize();
+        assert_eq!(*wide, [0u8; 64]);
+    }
+
+    #[test]
+    fn codex32_shares_restore_the_recovery_phrase() {
+        let path = DerivationPath::default();
+        for (language, word_count) in [(Language::English, 12), (Language::Japanese, 24)] {
+            let bundle = WalletBundle::generate_in(language, word_count, "", &path, Network::Mainnet).unwrap();
+            let shares = phrase_to_codex32(bundle.mnemonic(), 2, 3, None).unwrap();
+            let shares = [shares[2].to_string(), shares[0].to_string()];
+
+            let phrase = codex32_to_phrase(&shares, language).unwrap();
+            assert_eq!(phrase.as_str(), bundle.mnemonic());
+            let restored = derive_bundle_from_phrase(&phrase, "", &path).unwrap();
+            assert_eq!(restored.tos.address, bundle.tos.address);
+            assert_eq!(restored.uno.address, bundle.uno.address);
+        }
+
+        let not_entropy = split_codex32(&[1; 18], 1, 1, None).unwrap()[0].to_string();
+        assert!(matches!(codex32_to_phrase(&[not_entropy], Language::English), Err(WalletError::InvalidShare(_))));
+    }
 }
@@ -0,0 +1,150 @@
+//! EIP-191 `personal_sign` messages signed with the TOS secp256k1 key.
+//!
+//! The signed digest is `Keccak256("\x19Ethereum Signed Message:\n" ||
+//! decimal length || message)`, the prefix geth and GTOS use for
+//! `personal_sign`. Signatures are 65 bytes `r || s || v` with a deterministic
+//! RFC6979 nonce, low `s` and `v` = 27 or 28. The signer is identified by
+//! recovering its public key and comparing the TOS hex address, so a paper
+//! wallet owner can prove control of an address without going online.
+
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+use crate::{tos_address, validate_hex_address, WalletError};
+
+/// Size of a recoverable `r || s || v` signature.
+pub const SIGNATURE_SIZE: usize = 65;
+
+const PERSONAL_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// EIP-191 version `0x45` digest of `message`.
+pub fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(PERSONAL_MESSAGE_PREFIX);
+    hasher.update(message.len().to_string().as_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Signs `message` with a secp256k1 private key such as the one in
+/// [`crate::derive_tos_wallet`]'s entry.
+pub fn sign_personal_message(
+    private_key: &[u8; 32],
+    message: &[u8],
+) -> Result<[u8; SIGNATURE_SIZE], WalletError> {
+    let signing_key = SigningKey::from_bytes(private_key.into())
+        .map_err(|err| WalletError::InvalidPrivateKey(format!("secp256k1: {err}")))?;
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(&personal_message_hash(message))
+        .map_err(|err| WalletError::InvalidSignature(err.to_string()))?;
+
+    let mut output = [0u8; SIGNATURE_SIZE];
+    output[..64].copy_from_slice(&signature.to_bytes());
+    output[64] = 27 + recovery_id.to_byte();
+    Ok(output)
+}
+
+/// Recovers the checksummed TOS address that signed `message`.
+///
+/// `v` may be 27/28 or 0/1. High-`s` signatures are rejected so each message
+/// has exactly one valid signature per key.
+pub fn recover_personal_message_address(message: &[u8], signature: &[u8]) -> Result<String, WalletError> {
+    if signature.len() != SIGNATURE_SIZE {
+        return Err(WalletError::InvalidSignature(format!(
+            "signature is {} bytes, expected {SIGNATURE_SIZE}",
+            signature.len()
+        )));
+    }
+
+    let recovery_id = match signature[64] {
+        v @ (27 | 28) => RecoveryId::from_byte(v - 27),
+        v @ (0 | 1) => RecoveryId::from_byte(v),
+        _ => None,
+    }
+    .ok_or_else(|| WalletError::InvalidSignature(format!("unsupported v value {}", signature[64])))?;
+
+    let rs = Signature::from_slice(&signature[..64])
+        .map_err(|_| WalletError::InvalidSignature("r or s is out of range".to_string()))?;
+    if rs.normalize_s().is_some() {
+        return Err(WalletError::InvalidSignature("s is not in the lower half of the curve order".to_string()));
+    }
+
+    let public_key = VerifyingKey::recover_from_prehash(&personal_message_hash(message), &rs, recovery_id)
+        .map_err(|_| WalletError::InvalidSignature("no public key recovers from this signature".to_string()))?;
+    Ok(tos_address(public_key.to_encoded_point(false).as_bytes()))
+}
+
+/// Checks that `signature` over `message` was made by the key behind the
+/// `0x` hex `address`.
+///
+/// Returns `Ok(false)` for a well-formed signature by a different key; a
+/// malformed signature or address is an error.
+pub fn verify_personal_message(address: &str, message: &[u8], signature: &[u8]) -> Result<bool, WalletError> {
+    let expected = validate_hex_address(address)?;
+    let recovered = recover_personal_message_address(message, signature)?;
+    Ok(recovered == expected.address())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // web3.js `eth.accounts.sign("Some data", key)` documentation example.
+    const PRIVATE_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const MESSAGE: &[u8] = b"Some data";
+    const MESSAGE_HASH: &str = "1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655";
+    const SIGNATURE: &str = "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd\
+                             6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029\
+                             1c";
+
+    fn private_key() -> zeroize::Zeroizing<[u8; 32]> {
+        crate::parse_private_key_hex(PRIVATE_KEY).unwrap()
+    }
+
+    fn signer_address() -> String {
+        let public_key = crate::secp256k1_public_key(&private_key(), false).unwrap();
+        tos_address(&public_key)
+    }
+
+    #[test]
+    fn signatures_match_the_reference_vector_and_recover() {
+        assert_eq!(hex::encode(personal_message_hash(MESSAGE)), MESSAGE_HASH);
+
+        let signature = sign_personal_message(&private_key(), MESSAGE).unwrap();
+        assert_eq!(hex::encode(signature), SIGNATURE);
+        assert_eq!(recover_personal_message_address(MESSAGE, &signature).unwrap(), signer_address());
+        assert!(verify_personal_message(&signer_address().to_lowercase(), MESSAGE, &signature).unwrap());
+        assert!(!verify_personal_message(&signer_address(), b"Other data", &signature).unwrap());
+
+        let mut zero_based_v = signature;
+        zero_based_v[64] -= 27;
+        assert!(verify_personal_message(&signer_address(), MESSAGE, &zero_based_v).unwrap());
+    }
+
+    #[test]
+    fn malformed_and_malleable_signatures_are_rejected() {
+        let signature = sign_personal_message(&private_key(), MESSAGE).unwrap();
+
+        let mut bad_v = signature;
+        bad_v[64] = 29;
+
+        // (r, n - s) with the flipped recovery id recovers the same key.
+        let rs = Signature::from_slice(&signature[..64]).unwrap();
+        let high_s = Signature::from_scalars(rs.r(), -*rs.s()).unwrap();
+        let mut malleated = [0u8; SIGNATURE_SIZE];
+        malleated[..64].copy_from_slice(&high_s.to_bytes());
+        malleated[64] = signature[64] ^ 1;
+
+        for signature in [&signature[..64], &bad_v[..], &malleated[..], &[0u8; SIGNATURE_SIZE][..]] {
+            assert!(matches!(
+                recover_personal_message_address(MESSAGE, signature),
+                Err(WalletError::InvalidSignature(_))
+            ));
+        }
+
+        assert!(matches!(
+            sign_personal_message(&[0u8; 32], MESSAGE),
+            Err(WalletError::InvalidPrivateKey(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::english_words::WORDS;
+use crate::WalletError;
 use curve25519_dalek::scalar::Scalar;
+use zeroize::Zeroizing;
 
 const KEY_SIZE: usize = 32;
 const SEED_LENGTH: usize = 24;
@@ -27,29 +29,40 @@ fn calculate_checksum_index(words: &[&str]) -> u32 {
 }
 
 /// Convert words to a Scalar (private key)
-pub fn words_to_scalar(words: &[&str]) -> Result<Scalar, String> {
+pub fn words_to_scalar(words: &[&str]) -> Result<Scalar, WalletError> {
     if !(words.len() == SEED_LENGTH + 1 || words.len() == SEED_LENGTH) {
-        return Err("Invalid words count".to_string());
+        return Err(WalletError::InvalidMnemonic(format!(
+            "legacy seeds have {} or {} words, got {}",
+            SEED_LENGTH,
+            SEED_LENGTH + 1,
+            words.len()
+        )));
     }
 
     // Verify checksum if 25 words
     if words.len() == SEED_LENGTH + 1 {
         let checksum_index = calculate_checksum_index(&words[0..SEED_LENGTH]);
         if words[checksum_index as usize] != words[SEED_LENGTH] {
-            return Err("Invalid checksum".to_string());
+            return Err(WalletError::InvalidMnemonic(
+                "checksum word does not match; check the spelling and order of the words".to_string(),
+            ));
         }
     }
 
     // Find word indices
-    let mut indices = Vec::new();
-    for word in &words[0..SEED_LENGTH] {
+    let mut indices = Zeroizing::new(Vec::with_capacity(SEED_LENGTH));
+    for (position, word) in words[0..SEED_LENGTH].iter().enumerate() {
         let index = WORDS.iter().position(|w| w.eq_ignore_ascii_case(word))
-            .ok_or_else(|| format!("Word not found: {}", word))?;
+            .ok_or_else(|| WalletError::InvalidMnemonic(format!(
+                "word {} ({:?}) is not in the legacy TOS wordlist",
+                position + 1,
+                word
+            )))?;
         indices.push(index);
     }
 
     // Convert indices to bytes
-    let mut dest = Vec::with_capacity(KEY_SIZE);
+    let mut dest = Zeroizing::new(Vec::with_capacity(KEY_SIZE));
     for i in (0..SEED_LENGTH).step_by(3) {
         let a = indices[i];
         let b = indices[i + 1];
@@ -57,22 +70,22 @@ pub fn words_to_scalar(words: &[&str]) -> Result<Scalar, String> {
 
         let val = a + WORDS_LIST * (((WORDS_LIST - a) + b) % WORDS_LIST) + WORDS_LIST * WORDS_LIST * (((WORDS_LIST - b) + c) % WORDS_LIST);
         if val % WORDS_LIST != a {
-            return Err("Word list sanity check error".to_string());
+            return Err(WalletError::InvalidMnemonic("word list sanity check failed".to_string()));
         }
 
         let val = val as u32;
         dest.extend_from_slice(&val.to_le_bytes());
     }
 
-    let key_bytes: [u8; 32] = dest.try_into()
-        .map_err(|_| "Invalid key from bytes".to_string())?;
+    let mut key_bytes = Zeroizing::new([0u8; KEY_SIZE]);
+    key_bytes.copy_from_slice(&dest);
 
-    Ok(Scalar::from_bytes_mod_order(key_bytes))
+    Ok(Scalar::from_bytes_mod_order(*key_bytes))
 }
 
 /// Convert a private key (scalar) to a 25-word seed phrase (24 words + 1 checksum word)
 pub fn scalar_to_words(scalar: &Scalar) -> Vec<String> {
-    let bytes = scalar.to_bytes();
+    let bytes = Zeroizing::new(scalar.to_bytes());
 
     let mut words = Vec::with_capacity(SEED_LENGTH + 1);
     for i in (0..KEY_SIZE).step_by(4) {
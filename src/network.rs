@@ -0,0 +1,89 @@
+//! Mainnet / testnet selection.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WalletError;
+
+/// Network that wallet material is generated for.
+///
+/// The network picks the bech32 prefix, the signing chain ID and the display
+/// labels, and is stored in every bundle so a printed testnet wallet always
+/// says so. Hex addresses and key derivation are the same on both networks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// Parses `"mainnet"` or `"testnet"`.
+    pub fn parse(code: &str) -> Result<Self, WalletError> {
+        match code.trim().to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            _ => Err(WalletError::UnsupportedNetwork(code.to_string())),
+        }
+    }
+
+    /// Lowercase code, `mainnet` or `testnet`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet",
+        }
+    }
+
+    /// Bech32 human-readable prefix, `tos` or `tst`.
+    pub fn address_hrp(self) -> &'static str {
+        match self {
+            Self::Mainnet => "tos",
+            Self::Testnet => "tst",
+        }
+    }
+
+    /// GTOS chain ID that transactions are signed for.
+    ///
+    /// These values have not been checked against a GTOS chain config, as no
+    /// GTOS source is vendored here. Confirm them against the chain ID a
+    /// mainnet and a testnet node report before relying on signed
+    /// transactions.
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Self::Mainnet => 1666,
+            Self::Testnet => 1667,
+        }
+    }
+
+    /// Network for a bech32 prefix, if it is a TOS one.
+    pub fn from_address_hrp(hrp: &str) -> Option<Self> {
+        [Self::Mainnet, Self::Testnet]
+            .into_iter()
+            .find(|network| network.address_hrp() == hrp)
+    }
+
+    /// Display label for an asset, e.g. `TOS` or `TOS Testnet`.
+    pub fn label(self, asset: &str) -> String {
+        match self {
+            Self::Mainnet => asset.to_string(),
+            Self::Testnet => format!("{asset} Testnet"),
+        }
+    }
+}
+
+impl zeroize::DefaultIsZeroes for Network {}
+
+impl std::str::FromStr for Network {
+    type Err = WalletError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Self::parse(code)
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
@@ -0,0 +1,279 @@
+//! Schnorr signatures over Ristretto for UNO and legacy TOS keys.
+//!
+//! Both key kinds publish `P = H * x` for a generator `H`, where `x` is the
+//! inverted secret `s^-1` for UNO keys and the secret `s` itself for legacy
+//! keys. A signature is the 64 bytes `s || e` of the TOS/GTOS scheme:
+//!
+//! ```text
+//! R = H * k
+//! e = Sha3_512(P || message || R) mod l
+//! s = x * e + k
+//! ```
+//!
+//! and verifies when `e == Sha3_512(P || message || H * s - P * e)`. The
+//! nonce `k` is derived from the secret and the message, so signing needs no
+//! random number generator and the same message always gives the same
+//! signature.
+
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use sha3::{Digest, Sha3_512};
+use zeroize::Zeroizing;
+
+use crate::legacy::legacy_generator_h;
+use crate::{elgamal_generator_h, WalletError};
+
+/// Size of an `s || e` Schnorr signature.
+pub const SCHNORR_SIGNATURE_SIZE: usize = 64;
+
+const NONCE_DOMAIN: &[u8] = b"TOS_SCHNORR_NONCE";
+
+/// Which Ristretto key a Schnorr signature is made with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchnorrScheme {
+    /// UNO ElGamal key from [`crate::derive_uno_wallet`], public key `H * s^-1`.
+    Uno,
+    /// Legacy 25-word TOS key, public key `H * s` with
+    /// `TOS_SIGNATURE_GENERATOR_H`.
+    Legacy,
+}
+
+impl SchnorrScheme {
+    /// Parses `"uno"` or `"legacy"`.
+    pub fn parse(code: &str) -> Result<Self, WalletError> {
+        match code.trim().to_ascii_lowercase().as_str() {
+            "uno" => Ok(Self::Uno),
+            "legacy" => Ok(Self::Legacy),
+            _ => Err(WalletError::InvalidOptions(format!(
+                "unknown Schnorr scheme {code:?}: must be \"uno\" or \"legacy\""
+            ))),
+        }
+    }
+
+    /// Lowercase code, `uno` or `legacy`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Uno => "uno",
+            Self::Legacy => "legacy",
+        }
+    }
+
+    fn generator(self) -> RistrettoPoint {
+        match self {
+            Self::Uno => elgamal_generator_h(),
+            Self::Legacy => legacy_generator_h(),
+        }
+    }
+
+    /// The scalar `x` with `P = H * x` for a private key.
+    fn signing_scalar(self, secret: &Scalar) -> Zeroizing<Scalar> {
+        match self {
+            Self::Uno => Zeroizing::new(secret.invert()),
+            Self::Legacy => Zeroizing::new(*secret),
+        }
+    }
+}
+
+/// Signs `message` with a 32-byte little-endian Ristretto private key, as
+/// stored in the UNO or legacy wallet entry.
+pub fn sign_schnorr(
+    scheme: SchnorrScheme,
+    private_key: &[u8; 32],
+    message: &[u8],
+) -> Result<[u8; SCHNORR_SIGNATURE_SIZE], WalletError> {
+    let secret = Scalar::from_canonical_bytes(*private_key)
+        .into_option()
+        .map(Zeroizing::new)
+        .ok_or_else(|| WalletError::InvalidPrivateKey("Ristretto key is not a canonical scalar".to_string()))?;
+    if *secret == Scalar::ZERO {
+        return Err(WalletError::ZeroKey);
+    }
+
+    let generator = scheme.generator();
+    let x = scheme.signing_scalar(&secret);
+    let public_key = (generator * *x).compress();
+
+    let mut nonce_hash = Sha3_512::new();
+    nonce_hash.update(NONCE_DOMAIN);
+    nonce_hash.update(scheme.code().as_bytes());
+    nonce_hash.update(Zeroizing::new(x.to_bytes()).as_slice());
+    nonce_hash.update(public_key.as_bytes());
+    nonce_hash.update(message);
+    let mut wide = Zeroizing::new([0u8; 64]);
+    wide.copy_from_slice(&nonce_hash.finalize());
+    let k = Zeroizing::new(Scalar::from_bytes_mod_order_wide(&wide));
+
+    let e = challenge(&public_key, message, &(generator * *k));
+    let s = *x * e + *k;
+
+    let mut signature = [0u8; SCHNORR_SIGNATURE_SIZE];
+    signature[..32].copy_from_slice(s.as_bytes());
+    signature[32..].copy_from_slice(e.as_bytes());
+    Ok(signature)
+}
+
+/// Checks an `s || e` signature over `message` against a compressed
+/// Ristretto public key.
+///
+/// Returns `Ok(false)` when the signature is well-formed but does not match;
+/// a malformed key or signature is an error.
+pub fn verify_schnorr(
+    scheme: SchnorrScheme,
+    public_key: &[u8; 32],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, WalletError> {
+    if signature.len() != SCHNORR_SIGNATURE_SIZE {
+        return Err(WalletError::InvalidSignature(format!(
+            "signature is {} bytes, expected {SCHNORR_SIGNATURE_SIZE}",
+            signature.len()
+        )));
+    }
+
+    let compressed = CompressedRistretto(*public_key);
+    let point = compressed
+        .decompress()
+        .ok_or_else(|| WalletError::InvalidPublicKey("not a valid Ristretto point".to_string()))?;
+    let s = canonical_scalar(&signature[..32], "s")?;
+    let e = canonical_scalar(&signature[32..], "e")?;
+
+    let r = scheme.generator() * s - point * e;
+    Ok(challenge(&compressed, message, &r) == e)
+}
+
+fn challenge(public_key: &CompressedRistretto, message: &[u8], r: &RistrettoPoint) -> Scalar {
+    let mut hasher = Sha3_512::new();
+    hasher.update(public_key.as_bytes());
+    hasher.update(message);
+    hasher.update(r.compress().as_bytes());
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+fn canonical_scalar(bytes: &[u8], name: &str) -> Result<Scalar, WalletError> {
+    let mut array = [0u8; 32];
+    array.copy_from_slice(bytes);
+    Scalar::from_canonical_bytes(array)
+        .into_option()
+        .ok_or_else(|| WalletError::InvalidSignature(format!("{name} is not a canonical scalar")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // UNO key of the `abandon … art` test mnemonic and legacy wallet #1 from
+    // tests/regression_test.rs.
+    const UNO_PRIVATE_KEY: &str = "185721a1028bc0a87b5fb788bf9223e8ee6a8426b241ce2447920a77c9759207";
+    const UNO_PUBLIC_KEY: &str = "48567021150b09cfa3895e0cc6bd21c836d65b7a8e2f08868ce89bae7b021d38";
+    const LEGACY_PRIVATE_KEY: &str = "f164f0cd577136547bd0b939050d596ec683d18341fb957a8f462be2c6b1330f";
+    const LEGACY_PUBLIC_KEY: &str = "aa17efea5456e001f01a5dc58aee3d4077059faa7b49365c42dc30261f2d0b1c";
+    const MESSAGE: &[u8] = b"TOS paper wallet";
+    // Deterministic signatures of MESSAGE, pinned against regressions.
+    const UNO_SIGNATURE: &str = "ad67e9ece5498f0f79bf07261bb85b3782872b033b935065048cbfa645abbb0b\
+                                 713eafb43e7dbd28e2c950a4952f75119214e3c9034309668a71f98ddd5a9f09";
+    const LEGACY_SIGNATURE: &str = "33e16fde0fd7645fe7eac95963133a8fc0eea6c37367157a8266a61812c68a0e\
+                                    15530fcd2512dd2aa6e9bb586ec3cd2a0df09c6f2ed1d3af30bdd9608e69b80b";
+    // Random-nonce signatures of MESSAGE from the standard-library Python
+    // ristretto255 in tools/schnorr-reference, not from this crate's signer:
+    // `python3 schnorr.py sign <uno|legacy> <private key> "TOS paper wallet"`.
+    const EXTERNAL_SIGNATURES: [(SchnorrScheme, &str, &str); 2] = [
+        (
+            SchnorrScheme::Uno,
+            UNO_PUBLIC_KEY,
+            "41e82623e7d1be291b2e491b7aa022f70d124cc151510e49f99ba3004c574b01\
+             df0913f7aaa7aa4dbe405e7b12155aa09ee3cc5954b7451701946c27a376760e",
+        ),
+        (
+            SchnorrScheme::Legacy,
+            LEGACY_PUBLIC_KEY,
+            "f087c49314958b42fa11d0b428f240749702cb284ff548d9950460ecbd538005\
+             2215352b1868058b17248be8a44b2a916668fadf7085daa4984554faf2f7e90f",
+        ),
+    ];
+
+    fn key(hex_key: &str) -> [u8; 32] {
+        hex::decode(hex_key).unwrap().try_into().unwrap()
+    }
+
+    fn keys() -> [(SchnorrScheme, [u8; 32], [u8; 32], &'static str); 2] {
+        [
+            (SchnorrScheme::Uno, key(UNO_PRIVATE_KEY), key(UNO_PUBLIC_KEY), UNO_SIGNATURE),
+            (SchnorrScheme::Legacy, key(LEGACY_PRIVATE_KEY), key(LEGACY_PUBLIC_KEY), LEGACY_SIGNATURE),
+        ]
+    }
+
+    #[test]
+    fn signatures_verify_only_for_their_key_message_and_scheme() {
+        for (scheme, private_key, public_key, expected) in keys() {
+            let signature = sign_schnorr(scheme, &private_key, MESSAGE).unwrap();
+            assert_eq!(hex::encode(signature), expected);
+            assert!(verify_schnorr(scheme, &public_key, MESSAGE, &signature).unwrap());
+            assert!(!verify_schnorr(scheme, &public_key, b"other message", &signature).unwrap());
+
+            let other = match scheme {
+                SchnorrScheme::Uno => SchnorrScheme::Legacy,
+                SchnorrScheme::Legacy => SchnorrScheme::Uno,
+            };
+            assert!(!verify_schnorr(other, &public_key, MESSAGE, &signature).unwrap());
+
+            let mut tampered = signature;
+            tampered[0] ^= 1;
+            assert!(!verify_schnorr(scheme, &public_key, MESSAGE, &tampered).unwrap());
+        }
+    }
+
+    #[test]
+    fn verification_accepts_any_nonce() {
+        // Signatures from a randomized signer, as GTOS produces them, verify
+        // the same way as the deterministic ones.
+        for (scheme, private_key, public_key, _) in keys() {
+            let secret = Scalar::from_canonical_bytes(private_key).unwrap();
+            let x = scheme.signing_scalar(&secret);
+            let k = Scalar::from_bytes_mod_order_wide(&[7u8; 64]);
+            let e = challenge(&CompressedRistretto(public_key), MESSAGE, &(scheme.generator() * k));
+            let s = *x * e + k;
+
+            let mut signature = [0u8; SCHNORR_SIGNATURE_SIZE];
+            signature[..32].copy_from_slice(s.as_bytes());
+            signature[32..].copy_from_slice(e.as_bytes());
+            assert!(verify_schnorr(scheme, &public_key, MESSAGE, &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn external_signatures_verify() {
+        for (scheme, public_key, signature) in EXTERNAL_SIGNATURES {
+            let signature = hex::decode(signature).unwrap();
+            assert!(verify_schnorr(scheme, &key(public_key), MESSAGE, &signature).unwrap());
+            assert!(!verify_schnorr(scheme, &key(public_key), b"other message", &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn malformed_inputs_are_typed_errors() {
+        let public_key = key(UNO_PUBLIC_KEY);
+        let signature = sign_schnorr(SchnorrScheme::Uno, &key(UNO_PRIVATE_KEY), MESSAGE).unwrap();
+
+        let mut non_canonical = signature;
+        non_canonical[..32].copy_from_slice(&[0xff; 32]);
+        for bad in [&signature[..63], &non_canonical[..]] {
+            assert!(matches!(
+                verify_schnorr(SchnorrScheme::Uno, &public_key, MESSAGE, bad),
+                Err(WalletError::InvalidSignature(_))
+            ));
+        }
+
+        assert!(matches!(
+            verify_schnorr(SchnorrScheme::Uno, &[0xff; 32], MESSAGE, &signature),
+            Err(WalletError::InvalidPublicKey(_))
+        ));
+        assert_eq!(sign_schnorr(SchnorrScheme::Legacy, &[0; 32], MESSAGE), Err(WalletError::ZeroKey));
+        assert!(matches!(
+            sign_schnorr(SchnorrScheme::Legacy, &[0xff; 32], MESSAGE),
+            Err(WalletError::InvalidPrivateKey(_))
+        ));
+        assert!(SchnorrScheme::parse("ed25519").is_err());
+    }
+}
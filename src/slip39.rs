@@ -0,0 +1,641 @@
+//! SLIP-39 Shamir backups of the BIP39 entropy.
+//!
+//! The master secret (the entropy behind a 12–24 word recovery phrase) is
+//! encrypted under an optional passphrase with the SLIP-39 Feistel cipher,
+//! split into `group_threshold`-of-`groups` group secrets, and each group
+//! secret is split again into `member_threshold`-of-`member_count` member
+//! shares. Every share is printed as a 20 (128-bit) to 33 (256-bit) word
+//! mnemonic from the SLIP-39 wordlist, protected by an RS1024 checksum.
+//!
+//! New backups are created with the extendable flag set and iteration
+//! exponent [`SLIP39_ITERATION_EXPONENT`], as current Trezor firmware does.
+//! Shares with either flag value are accepted when combining. A wrong
+//! passphrase cannot be detected: it recovers a different, valid secret.
+
+use std::collections::BTreeMap;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::slip39_words::WORDS;
+use crate::WalletError;
+
+/// PBKDF2 cost exponent for new backups: `10000 << e` iterations in total.
+pub const SLIP39_ITERATION_EXPONENT: u8 = 1;
+/// Largest number of groups, or of members in one group.
+pub const MAX_SLIP39_SHARE_COUNT: u8 = 16;
+
+const RADIX_BITS: usize = 10;
+const HEADER_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+const MIN_MNEMONIC_WORDS: usize = 20;
+const MIN_SECRET_SIZE: usize = 16;
+const DIGEST_SIZE: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const BASE_ITERATION_COUNT: u32 = 10_000;
+const ROUND_COUNT: u8 = 4;
+const CUSTOMIZATION: &[u8] = b"shamir";
+const CUSTOMIZATION_EXTENDABLE: &[u8] = b"shamir_extendable";
+const RS1024_GENERATOR: [u32; 10] = [
+    0x00e0_e040,
+    0x01c1_c080,
+    0x0383_8100,
+    0x0707_0200,
+    0x0e0e_0009,
+    0x1c0c_2412,
+    0x3808_6c24,
+    0x3090_fc48,
+    0x21b1_f890,
+    0x03f3_f120,
+];
+
+/// `exp` and `log` tables of GF(256) with the Rijndael polynomial and
+/// generator 3.
+const fn gf256_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        poly ^= poly << 1;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11b;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const EXP: [u8; 255] = gf256_tables().0;
+const LOG: [u8; 256] = gf256_tables().1;
+
+/// Threshold and size of one SLIP-39 group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slip39Group {
+    /// Member shares needed to recover the group secret.
+    pub member_threshold: u8,
+    /// Member shares printed for the group.
+    pub member_count: u8,
+}
+
+impl Slip39Group {
+    /// A `member_threshold`-of-`member_count` group.
+    pub fn new(member_threshold: u8, member_count: u8) -> Self {
+        Self {
+            member_threshold,
+            member_count,
+        }
+    }
+}
+
+/// Splits `master_secret` into SLIP-39 mnemonics, one list per group.
+///
+/// Any `group_threshold` groups, each with `member_threshold` of its shares,
+/// recover the secret. `passphrase` may be empty and must be printable ASCII.
+pub fn split_slip39(
+    master_secret: &[u8],
+    group_threshold: u8,
+    groups: &[Slip39Group],
+    passphrase: &str,
+) -> Result<Vec<Vec<String>>, WalletError> {
+    if master_secret.len() < MIN_SECRET_SIZE || !master_secret.len().is_multiple_of(2) {
+        return Err(WalletError::InvalidOptions(format!(
+            "SLIP-39 secrets are an even number of bytes, at least {MIN_SECRET_SIZE}; got {}",
+            master_secret.len()
+        )));
+    }
+    if groups.is_empty() || groups.len() > usize::from(MAX_SLIP39_SHARE_COUNT) {
+        return Err(WalletError::InvalidOptions(format!(
+            "SLIP-39 backups have 1 to {MAX_SLIP39_SHARE_COUNT} groups, got {}",
+            groups.len()
+        )));
+    }
+    if group_threshold == 0 || usize::from(group_threshold) > groups.len() {
+        return Err(WalletError::InvalidOptions(format!(
+            "group threshold {group_threshold} must be between 1 and the {} groups",
+            groups.len()
+        )));
+    }
+    for (position, group) in groups.iter().enumerate() {
+        if group.member_threshold == 0
+            || group.member_threshold > group.member_count
+            || group.member_count > MAX_SLIP39_SHARE_COUNT
+        {
+            return Err(WalletError::InvalidOptions(format!(
+                "group {} is {}-of-{}: thresholds must be between 1 and a count of at most {MAX_SLIP39_SHARE_COUNT}",
+                position + 1,
+                group.member_threshold,
+                group.member_count
+            )));
+        }
+        if group.member_threshold == 1 && group.member_count > 1 {
+            return Err(WalletError::InvalidOptions(format!(
+                "group {} is 1-of-{}: use a 1-of-1 group instead of copies of the same share",
+                position + 1,
+                group.member_count
+            )));
+        }
+    }
+    check_passphrase(passphrase)?;
+
+    let mut identifier = [0u8; 2];
+    getrandom::getrandom(&mut identifier).map_err(|err| WalletError::Entropy(err.to_string()))?;
+    let identifier = u16::from_be_bytes(identifier) & 0x7fff;
+    let encrypted = feistel(
+        master_secret,
+        passphrase,
+        SLIP39_ITERATION_EXPONENT,
+        identifier,
+        true,
+        [0, 1, 2, 3],
+    );
+
+    let group_secrets = split_secret(group_threshold, groups.len() as u8, &encrypted)?;
+    groups
+        .iter()
+        .zip(group_secrets)
+        .map(|(group, (group_index, group_secret))| {
+            let members = split_secret(group.member_threshold, group.member_count, &group_secret)?;
+            Ok(members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable: true,
+                        iteration_exponent: SLIP39_ITERATION_EXPONENT,
+                        group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.member_threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Recovers the master secret from SLIP-39 mnemonics.
+///
+/// Exactly `group_threshold` groups must be present, each with exactly its
+/// member threshold of shares; repeated copies of a share are ignored.
+pub fn combine_slip39<S: AsRef<str>>(mnemonics: &[S], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+    check_passphrase(passphrase)?;
+    let shares = mnemonics
+        .iter()
+        .enumerate()
+        .map(|(position, mnemonic)| {
+            Share::parse(mnemonic.as_ref()).map_err(|err| match err {
+                WalletError::InvalidShare(reason) => WalletError::InvalidShare(format!("share {}: {reason}", position + 1)),
+                other => other,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares
+        .first()
+        .ok_or_else(|| WalletError::InvalidShare("no shares given".to_string()))?;
+
+    for share in &shares {
+        if share.identifier != first.identifier || share.extendable != first.extendable {
+            return Err(WalletError::InvalidShare("shares belong to different backups".to_string()));
+        }
+        if share.iteration_exponent != first.iteration_exponent {
+            return Err(WalletError::InvalidShare("shares have different iteration exponents".to_string()));
+        }
+        if share.group_threshold != first.group_threshold || share.group_count != first.group_count {
+            return Err(WalletError::InvalidShare("shares have different group settings".to_string()));
+        }
+        if share.value.len() != first.value.len() {
+            return Err(WalletError::InvalidShare("shares have different lengths".to_string()));
+        }
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        let members = groups.entry(share.group_index).or_default();
+        if let Some(other) = members.iter().find(|other| other.member_index == share.member_index) {
+            if other.value != share.value {
+                return Err(WalletError::InvalidShare(format!(
+                    "group {} has two different shares with member index {}",
+                    share.group_index + 1,
+                    share.member_index + 1
+                )));
+            }
+            continue;
+        }
+        if members.first().is_some_and(|other| other.member_threshold != share.member_threshold) {
+            return Err(WalletError::InvalidShare(format!(
+                "shares of group {} have different thresholds",
+                share.group_index + 1
+            )));
+        }
+        members.push(share);
+    }
+
+    if groups.len() != usize::from(first.group_threshold) {
+        return Err(WalletError::InvalidShare(format!(
+            "shares from {} groups given, exactly {} are needed",
+            groups.len(),
+            first.group_threshold
+        )));
+    }
+
+    let group_secrets = groups
+        .iter()
+        .map(|(&group_index, members)| {
+            let threshold = members[0].member_threshold;
+            if members.len() != usize::from(threshold) {
+                return Err(WalletError::InvalidShare(format!(
+                    "group {} has {} shares, exactly {threshold} are needed",
+                    group_index + 1,
+                    members.len()
+                )));
+            }
+            let points = members
+                .iter()
+                .map(|member| (member.member_index, member.value.as_slice()))
+                .collect::<Vec<_>>();
+            Ok((group_index, recover_secret(threshold, &points)?))
+        })
+        .collect::<Result<Vec<_>, WalletError>>()?;
+
+    let points = group_secrets
+        .iter()
+        .map(|(group_index, secret)| (*group_index, secret.as_slice()))
+        .collect::<Vec<_>>();
+    let encrypted = recover_secret(first.group_threshold, &points)?;
+    Ok(feistel(
+        &encrypted,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+        [3, 2, 1, 0],
+    ))
+}
+
+/// One decoded SLIP-39 mnemonic. Thresholds and counts are stored as their
+/// actual values, not the `- 1` of the wire format.
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    fn parse(mnemonic: &str) -> Result<Self, WalletError> {
+        let words = Zeroizing::new(
+            mnemonic
+                .split_whitespace()
+                .enumerate()
+                .map(|(position, word)| {
+                    let word = word.to_lowercase();
+                    WORDS
+                        .binary_search(&word.as_str())
+                        .map(|index| index as u32)
+                        .map_err(|_| {
+                            WalletError::InvalidShare(format!(
+                                "word {} ({word:?}) is not in the SLIP-39 wordlist",
+                                position + 1
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(WalletError::InvalidShare(format!(
+                "shares have at least {MIN_MNEMONIC_WORDS} words, got {}",
+                words.len()
+            )));
+        }
+
+        let value_words = words.len() - HEADER_WORDS - CHECKSUM_WORDS;
+        let padding = value_words * RADIX_BITS % 16;
+        if padding > 8 {
+            return Err(WalletError::InvalidShare(format!("{} words is not a valid share length", words.len())));
+        }
+
+        let id_exp = words[0] << RADIX_BITS | words[1];
+        let extendable = id_exp >> 4 & 1 == 1;
+        if rs1024_polymod(customization(extendable), &words) != 1 {
+            return Err(WalletError::InvalidShare(
+                "checksum does not match; check the spelling and order of the words".to_string(),
+            ));
+        }
+
+        let params = words[2] << RADIX_BITS | words[3];
+        let share = Self {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: (params >> 16) as u8,
+            group_threshold: (params >> 12 & 0xf) as u8 + 1,
+            group_count: (params >> 8 & 0xf) as u8 + 1,
+            member_index: (params >> 4 & 0xf) as u8,
+            member_threshold: (params & 0xf) as u8 + 1,
+            value: words_to_bytes(&words[HEADER_WORDS..words.len() - CHECKSUM_WORDS], padding)?,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(WalletError::InvalidShare(format!(
+                "group threshold {} is greater than the group count {}",
+                share.group_threshold, share.group_count
+            )));
+        }
+        Ok(share)
+    }
+
+    fn to_mnemonic(&self) -> String {
+        let id_exp = u32::from(self.identifier) << 5 | u32::from(self.extendable) << 4 | u32::from(self.iteration_exponent);
+        let params = u32::from(self.group_index) << 16
+            | u32::from(self.group_threshold - 1) << 12
+            | u32::from(self.group_count - 1) << 8
+            | u32::from(self.member_index) << 4
+            | u32::from(self.member_threshold - 1);
+
+        let mut words = Zeroizing::new(vec![
+            id_exp >> RADIX_BITS,
+            id_exp & 0x3ff,
+            params >> RADIX_BITS,
+            params & 0x3ff,
+        ]);
+        words.extend(bytes_to_words(&self.value));
+        words.extend([0; CHECKSUM_WORDS]);
+        let checksum = rs1024_polymod(customization(self.extendable), &words) ^ 1;
+        let len = words.len();
+        for (offset, word) in words[len - CHECKSUM_WORDS..].iter_mut().enumerate() {
+            *word = checksum >> (RADIX_BITS * (CHECKSUM_WORDS - 1 - offset)) & 0x3ff;
+        }
+
+        words.iter().map(|&word| WORDS[word as usize]).collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), WalletError> {
+    if passphrase.bytes().all(|byte| (0x20..=0x7e).contains(&byte)) {
+        Ok(())
+    } else {
+        Err(WalletError::InvalidOptions(
+            "SLIP-39 passphrases may only contain printable ASCII characters".to_string(),
+        ))
+    }
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        CUSTOMIZATION_EXTENDABLE
+    } else {
+        CUSTOMIZATION
+    }
+}
+
+fn rs1024_polymod(customization: &[u8], words: &[u32]) -> u32 {
+    let mut checksum = 1u32;
+    for value in customization.iter().map(|&byte| u32::from(byte)).chain(words.iter().copied()) {
+        let top = checksum >> 20;
+        checksum = (checksum & 0xfffff) << RADIX_BITS ^ value;
+        for (bit, generator) in RS1024_GENERATOR.iter().enumerate() {
+            if top >> bit & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// Packs bytes into 10-bit words, zero-padding on the left.
+fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
+    let word_count = (bytes.len() * 8).div_ceil(RADIX_BITS);
+    let mut words = Vec::with_capacity(word_count);
+    let mut buffer = 0u32;
+    let mut bits = word_count * RADIX_BITS - bytes.len() * 8;
+    for &byte in bytes {
+        buffer = buffer << 8 | u32::from(byte);
+        bits += 8;
+        if bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push(buffer >> bits & 0x3ff);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    words
+}
+
+/// Unpacks 10-bit words whose first `padding` bits must be zero.
+fn words_to_bytes(words: &[u32], padding: usize) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity((words.len() * RADIX_BITS - padding) / 8));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for (position, &word) in words.iter().enumerate() {
+        buffer = buffer << RADIX_BITS | word;
+        bits += RADIX_BITS;
+        if position == 0 {
+            bits -= padding;
+            if buffer >> bits != 0 {
+                return Err(WalletError::InvalidShare("padding bits are not zero".to_string()));
+            }
+        }
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+/// The four-round Feistel cipher of SLIP-39. Encrypts with rounds `0..4` and
+/// decrypts with the same rounds reversed.
+fn feistel(
+    secret: &[u8],
+    passphrase: &str,
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    rounds: [u8; ROUND_COUNT as usize],
+) -> Zeroizing<Vec<u8>> {
+    let half = secret.len() / 2;
+    let mut left = Zeroizing::new(secret[..half].to_vec());
+    let mut right = Zeroizing::new(secret[half..].to_vec());
+    let mut salt_prefix = Vec::new();
+    if !extendable {
+        salt_prefix.extend_from_slice(CUSTOMIZATION);
+        salt_prefix.extend_from_slice(&identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / u32::from(ROUND_COUNT);
+
+    for round in rounds {
+        let mut password = Zeroizing::new(vec![round]);
+        password.extend_from_slice(passphrase.as_bytes());
+        let mut salt = Zeroizing::new(salt_prefix.clone());
+        salt.extend_from_slice(&right);
+        let mut mask = Zeroizing::new(vec![0u8; half]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(&password, &salt, iterations, &mut mask);
+        for (byte, mask) in left.iter_mut().zip(mask.iter()) {
+            *byte ^= mask;
+        }
+        std::mem::swap(&mut left, &mut right);
+    }
+
+    let mut output = Zeroizing::new(Vec::with_capacity(secret.len()));
+    output.extend_from_slice(&right);
+    output.extend_from_slice(&left);
+    output
+}
+
+fn share_digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random_part).expect("HMAC accepts keys of any length");
+    mac.update(secret);
+    let mut digest = [0u8; DIGEST_SIZE];
+    digest.copy_from_slice(&mac.finalize().into_bytes()[..DIGEST_SIZE]);
+    digest
+}
+
+/// An x coordinate and the polynomial values at it, one per secret byte.
+type SharePoint = (u8, Zeroizing<Vec<u8>>);
+
+/// Splits `secret` into `count` points of a polynomial of degree
+/// `threshold - 1` that also passes through the secret at x = 255 and its
+/// digest at x = 254.
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Result<Vec<SharePoint>, WalletError> {
+    if threshold == 1 {
+        return Ok((0..count).map(|index| (index, Zeroizing::new(secret.to_vec()))).collect());
+    }
+
+    let random = |len: usize| {
+        let mut bytes = Zeroizing::new(vec![0u8; len]);
+        getrandom::getrandom(&mut bytes).map_err(|err| WalletError::Entropy(err.to_string()))?;
+        Ok::<_, WalletError>(bytes)
+    };
+    let mut shares = (0..threshold - 2)
+        .map(|index| Ok((index, random(secret.len())?)))
+        .collect::<Result<Vec<_>, WalletError>>()?;
+
+    let random_part = random(secret.len() - DIGEST_SIZE)?;
+    let mut digest_share = Zeroizing::new(share_digest(&random_part, secret).to_vec());
+    digest_share.extend_from_slice(&random_part);
+
+    let mut base = shares
+        .iter()
+        .map(|(index, value)| (*index, value.as_slice()))
+        .collect::<Vec<_>>();
+    base.push((DIGEST_INDEX, &digest_share));
+    base.push((SECRET_INDEX, secret));
+    let derived = (threshold - 2..count)
+        .map(|index| (index, interpolate(&base, index)))
+        .collect::<Vec<_>>();
+    shares.extend(derived);
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, &[u8])]) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+    if threshold == 1 {
+        return Ok(Zeroizing::new(shares[0].1.to_vec()));
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest_share = interpolate(shares, DIGEST_INDEX);
+    let (digest, random_part) = digest_share.split_at(DIGEST_SIZE);
+    if share_digest(random_part, &secret) != digest {
+        return Err(WalletError::InvalidShare(
+            "shares do not fit together; check that they come from the same backup".to_string(),
+        ));
+    }
+    Ok(secret)
+}
+
+/// Lagrange interpolation at `x` over GF(256), byte by byte. The share
+/// indices must be distinct and the values of equal length.
+fn interpolate(shares: &[(u8, &[u8])], x: u8) -> Zeroizing<Vec<u8>> {
+    if let Some((_, value)) = shares.iter().find(|(index, _)| *index == x) {
+        return Zeroizing::new(value.to_vec());
+    }
+
+    let log = |value: u8| u32::from(LOG[usize::from(value)]);
+    let log_product: u32 = shares.iter().map(|(index, _)| log(index ^ x)).sum();
+    let mut result = Zeroizing::new(vec![0u8; shares[0].1.len()]);
+    for (index, value) in shares {
+        let denominator: u32 = shares.iter().map(|(other, _)| log(index ^ other)).sum();
+        let log_basis = (log_product + 255 * 2 * shares.len() as u32 - log(index ^ x) - denominator) % 255;
+        for (output, &byte) in result.iter_mut().zip(value.iter()) {
+            if byte != 0 {
+                *output ^= EXP[((log(byte) + log_basis) % 255) as usize];
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "bb54aac4b89dc868ba37d9cc21b2cece";
+
+    #[test]
+    fn any_threshold_subset_recovers_the_secret() {
+        let secret = hex::decode(SECRET).unwrap();
+        let groups = [Slip39Group::new(1, 1), Slip39Group::new(2, 3), Slip39Group::new(3, 5)];
+        let shares = split_slip39(&secret, 2, &groups, "TREZOR").unwrap();
+        assert_eq!(shares.iter().map(Vec::len).collect::<Vec<_>>(), [1, 3, 5]);
+        assert!(shares.iter().flatten().all(|share| share.split(' ').count() == 20));
+
+        for subset in [
+            vec![&shares[0][0], &shares[1][2], &shares[1][0]],
+            vec![&shares[2][4], &shares[1][1], &shares[2][0], &shares[2][2], &shares[1][0]],
+            vec![&shares[0][0], &shares[2][1], &shares[2][3], &shares[2][4], &shares[0][0]],
+        ] {
+            assert_eq!(hex::encode(combine_slip39(&subset, "TREZOR").unwrap()), SECRET);
+            assert_ne!(hex::encode(combine_slip39(&subset, "").unwrap()), SECRET);
+        }
+
+        for too_few in [vec![&shares[0][0]], vec![&shares[0][0], &shares[2][0], &shares[2][1]]] {
+            assert!(matches!(combine_slip39(&too_few, "TREZOR"), Err(WalletError::InvalidShare(_))));
+        }
+    }
+
+    #[test]
+    fn shares_round_trip_and_detect_typos() {
+        let secret = [0x5au8; 32];
+        let shares = split_slip39(&secret, 1, &[Slip39Group::new(1, 1)], "").unwrap();
+        let share = &shares[0][0];
+        assert_eq!(share.split(' ').count(), 33);
+        assert_eq!(Share::parse(share).unwrap().to_mnemonic(), *share);
+        assert_eq!(combine_slip39(&[share.to_uppercase()], "").unwrap().as_slice(), secret);
+
+        let mut words = share.split(' ').collect::<Vec<_>>();
+        words[10] = if words[10] == "academic" { "acid" } else { "academic" };
+        assert!(matches!(combine_slip39(&[words.join(" ")], ""), Err(WalletError::InvalidShare(_))));
+        assert!(matches!(combine_slip39::<&str>(&[], ""), Err(WalletError::InvalidShare(_))));
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected() {
+        let secret = [1u8; 16];
+        for (threshold, groups, passphrase) in [
+            (1, vec![], ""),
+            (2, vec![Slip39Group::new(2, 3)], ""),
+            (1, vec![Slip39Group::new(1, 3)], ""),
+            (1, vec![Slip39Group::new(4, 3)], ""),
+            (1, vec![Slip39Group::new(2, 17)], ""),
+            (1, vec![Slip39Group::new(2, 3)], "pässword"),
+        ] {
+            assert!(matches!(
+                split_slip39(&secret, threshold, &groups, passphrase),
+                Err(WalletError::InvalidOptions(_))
+            ));
+        }
+        assert!(split_slip39(&secret[..15], 1, &[Slip39Group::new(1, 1)], "").is_err());
+    }
+}
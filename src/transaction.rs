@@ -0,0 +1,645 @@
+//! Offline signing of GTOS transactions with the TOS secp256k1 key.
+//!
+//! An unsigned transaction is read from JSON, RLP-encoded and signed without
+//! any network access. Transactions with `gasPrice` are legacy transactions
+//! signed with EIP-155 replay protection; transactions with `maxFeePerGas`
+//! and `maxPriorityFeePerGas` are EIP-1559 type `0x02` envelopes. Addresses
+//! are the 32-byte `0x` hex TOS addresses, so `to` is checked like any typed
+//! address (see [`crate::validate_hex_address`]).
+
+use k256::ecdsa::SigningKey;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::{secp256k1_public_key, tos_address, validate_hex_address, Network, WalletError};
+
+const EIP1559_TX_TYPE: u8 = 0x02;
+const MAX_QUANTITY_SIZE: usize = 32;
+const STORAGE_KEY_SIZE: usize = 32;
+
+/// Unsigned integer field of up to 256 bits, stored big-endian without
+/// leading zeros.
+///
+/// JSON accepts a number, a decimal string or a `0x` hex string.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Quantity(Vec<u8>);
+
+impl Quantity {
+    /// Parses a decimal or `0x` hex string.
+    pub fn parse(text: &str) -> Result<Self, WalletError> {
+        let text = text.trim();
+        let bytes = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            Some(digits) => parse_hex_quantity(digits)?,
+            None => parse_decimal_quantity(text)?,
+        };
+        Ok(Self(strip_leading_zeros(&bytes).to_vec()))
+    }
+
+    fn as_u64(&self, name: &str) -> Result<u64, WalletError> {
+        if self.0.len() > 8 {
+            return Err(WalletError::InvalidTransaction(format!("{name} does not fit in 64 bits")));
+        }
+        Ok(self.0.iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+    }
+}
+
+impl From<u64> for Quantity {
+    fn from(value: u64) -> Self {
+        Self(strip_leading_zeros(&value.to_be_bytes()).to_vec())
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum QuantityInput {
+            Number(u64),
+            Text(String),
+        }
+
+        match QuantityInput::deserialize(deserializer)? {
+            QuantityInput::Number(value) => Ok(Self::from(value)),
+            QuantityInput::Text(text) => Self::parse(&text).map_err(de::Error::custom),
+        }
+    }
+}
+
+/// One EIP-2930 access list entry of an EIP-1559 transaction.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccessListItem {
+    address: String,
+    #[serde(default)]
+    storage_keys: Vec<String>,
+}
+
+/// An unsigned GTOS transaction as typed into or exported by a wallet.
+///
+/// `gas` may also be spelled `gasLimit` and `data` may be spelled `input`.
+/// Omitting `to` (or passing an empty string) creates a contract. Omitting
+/// `chainId` signs for the network's chain ID.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UnsignedTransaction {
+    #[serde(rename = "type", default)]
+    tx_type: Option<Quantity>,
+    #[serde(default)]
+    chain_id: Option<Quantity>,
+    nonce: Quantity,
+    #[serde(alias = "gasLimit")]
+    gas: Quantity,
+    #[serde(default)]
+    to: Option<String>,
+    #[serde(default)]
+    value: Quantity,
+    #[serde(default, alias = "input")]
+    data: Option<String>,
+    #[serde(default)]
+    gas_price: Option<Quantity>,
+    #[serde(default)]
+    max_fee_per_gas: Option<Quantity>,
+    #[serde(default)]
+    max_priority_fee_per_gas: Option<Quantity>,
+    #[serde(default)]
+    access_list: Option<Vec<AccessListItem>>,
+    #[serde(skip)]
+    network: Network,
+}
+
+/// A signed transaction ready to broadcast from an online machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SignedTransaction {
+    kind: &'static str,
+    from: String,
+    hash: String,
+    raw: String,
+}
+
+impl SignedTransaction {
+    /// `legacy` or `eip1559`.
+    pub fn kind(&self) -> &str {
+        self.kind
+    }
+
+    /// Checksummed TOS address of the signing key.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// `0x` Keccak256 hash of the raw transaction.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// `0x` hex raw signed transaction for `eth_sendRawTransaction`.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl UnsignedTransaction {
+    /// Parses the JSON form of an unsigned transaction for `network`.
+    pub fn from_json(json: &str, network: Network) -> Result<Self, WalletError> {
+        let transaction: Self =
+            serde_json::from_str(json).map_err(|err| WalletError::InvalidTransaction(err.to_string()))?;
+        Ok(Self { network, ..transaction })
+    }
+
+    /// Signs the transaction with a secp256k1 private key such as the one in
+    /// [`crate::derive_tos_wallet`]'s entry.
+    pub fn sign(&self, private_key: &[u8; 32]) -> Result<SignedTransaction, WalletError> {
+        self.fields()?.sign(private_key)
+    }
+
+    fn fields(&self) -> Result<TransactionFields, WalletError> {
+        let chain_id = self.network.chain_id();
+        if let Some(given) = &self.chain_id {
+            if given.as_u64("chainId")? != chain_id {
+                return Err(WalletError::InvalidTransaction(format!(
+                    "chainId is not the {} chain ID {chain_id}",
+                    self.network
+                )));
+            }
+        }
+
+        let fee = match (&self.gas_price, &self.max_fee_per_gas, &self.max_priority_fee_per_gas) {
+            (Some(gas_price), None, None) => {
+                if self.access_list.is_some() {
+                    return Err(WalletError::InvalidTransaction(
+                        "accessList requires an EIP-1559 transaction".to_string(),
+                    ));
+                }
+                Fee::Legacy {
+                    gas_price: gas_price.clone(),
+                }
+            }
+            (None, Some(max_fee), Some(max_priority_fee)) => {
+                if compare_quantities(max_priority_fee, max_fee).is_gt() {
+                    return Err(WalletError::InvalidTransaction(
+                        "maxPriorityFeePerGas is higher than maxFeePerGas".to_string(),
+                    ));
+                }
+                Fee::Eip1559 {
+                    max_priority_fee_per_gas: max_priority_fee.clone(),
+                    max_fee_per_gas: max_fee.clone(),
+                }
+            }
+            _ => {
+                return Err(WalletError::InvalidTransaction(
+                    "set either gasPrice, or both maxFeePerGas and maxPriorityFeePerGas".to_string(),
+                ))
+            }
+        };
+
+        let expected_type = match fee {
+            Fee::Legacy { .. } => 0,
+            Fee::Eip1559 { .. } => u64::from(EIP1559_TX_TYPE),
+        };
+        if let Some(tx_type) = &self.tx_type {
+            if tx_type.as_u64("type")? != expected_type {
+                return Err(WalletError::InvalidTransaction(format!(
+                    "type does not match the fee fields, expected {expected_type}"
+                )));
+            }
+        }
+
+        let to = match self.to.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(to) => Some(parse_address(to)?),
+        };
+
+        let access_list = self
+            .access_list
+            .iter()
+            .flatten()
+            .map(|item| {
+                let storage_keys = item
+                    .storage_keys
+                    .iter()
+                    .map(|key| parse_fixed_hex(key, STORAGE_KEY_SIZE, "storage key"))
+                    .collect::<Result<_, _>>()?;
+                Ok((parse_address(&item.address)?, storage_keys))
+            })
+            .collect::<Result<_, WalletError>>()?;
+
+        Ok(TransactionFields {
+            chain_id,
+            nonce: self.nonce.as_u64("nonce")?,
+            gas: self.gas.as_u64("gas")?,
+            to,
+            value: self.value.clone(),
+            data: parse_hex(self.data.as_deref().unwrap_or(""), "data")?,
+            fee,
+            access_list,
+        })
+    }
+}
+
+enum Fee {
+    Legacy {
+        gas_price: Quantity,
+    },
+    Eip1559 {
+        max_priority_fee_per_gas: Quantity,
+        max_fee_per_gas: Quantity,
+    },
+}
+
+/// Validated transaction fields, independent of the JSON spelling.
+struct TransactionFields {
+    chain_id: u64,
+    nonce: u64,
+    gas: u64,
+    to: Option<Vec<u8>>,
+    value: Quantity,
+    data: Vec<u8>,
+    fee: Fee,
+    access_list: Vec<(Vec<u8>, Vec<Vec<u8>>)>,
+}
+
+impl TransactionFields {
+    fn sign(&self, private_key: &[u8; 32]) -> Result<SignedTransaction, WalletError> {
+        let signing_key = SigningKey::from_bytes(private_key.into())
+            .map_err(|err| WalletError::InvalidPrivateKey(format!("secp256k1: {err}")))?;
+        let from = tos_address(&secp256k1_public_key(private_key, false)?);
+
+        let mut items = self.payload_items();
+        let (kind, raw) = match self.fee {
+            Fee::Legacy { .. } => {
+                // EIP-155: sign over (…, chainId, 0, 0) and fold the chain ID into v.
+                let mut unsigned = items.clone();
+                unsigned.extend([encode_uint(&self.chain_id.to_be_bytes()), encode_bytes(&[]), encode_bytes(&[])]);
+                let (r, s, recovery_id) = sign_digest(&signing_key, &encode_list(&unsigned))?;
+
+                let v = u128::from(self.chain_id) * 2 + 35 + u128::from(recovery_id);
+                items.extend([encode_uint(&v.to_be_bytes()), encode_uint(&r), encode_uint(&s)]);
+                ("legacy", encode_list(&items))
+            }
+            Fee::Eip1559 { .. } => {
+                let mut unsigned = vec![EIP1559_TX_TYPE];
+                unsigned.extend(encode_list(&items));
+                let (r, s, y_parity) = sign_digest(&signing_key, &unsigned)?;
+
+                items.extend([encode_uint(&[y_parity]), encode_uint(&r), encode_uint(&s)]);
+                let mut raw = vec![EIP1559_TX_TYPE];
+                raw.extend(encode_list(&items));
+                ("eip1559", raw)
+            }
+        };
+
+        Ok(SignedTransaction {
+            kind,
+            from,
+            hash: format!("0x{}", hex::encode(Keccak256::digest(&raw))),
+            raw: format!("0x{}", hex::encode(&raw)),
+        })
+    }
+
+    /// RLP items shared by the signing payload and the signed transaction.
+    fn payload_items(&self) -> Vec<Vec<u8>> {
+        let to = encode_bytes(self.to.as_deref().unwrap_or_default());
+        let value = encode_uint(&self.value.0);
+        let data = encode_bytes(&self.data);
+
+        match &self.fee {
+            Fee::Legacy { gas_price } => vec![
+                encode_uint(&self.nonce.to_be_bytes()),
+                encode_uint(&gas_price.0),
+                encode_uint(&self.gas.to_be_bytes()),
+                to,
+                value,
+                data,
+            ],
+            Fee::Eip1559 {
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+            } => {
+                let access_list = self
+                    .access_list
+                    .iter()
+                    .map(|(address, keys)| {
+                        let keys = keys.iter().map(|key| encode_bytes(key)).collect::<Vec<_>>();
+                        encode_list(&[encode_bytes(address), encode_list(&keys)])
+                    })
+                    .collect::<Vec<_>>();
+                vec![
+                    encode_uint(&self.chain_id.to_be_bytes()),
+                    encode_uint(&self.nonce.to_be_bytes()),
+                    encode_uint(&max_priority_fee_per_gas.0),
+                    encode_uint(&max_fee_per_gas.0),
+                    encode_uint(&self.gas.to_be_bytes()),
+                    to,
+                    value,
+                    data,
+                    encode_list(&access_list),
+                ]
+            }
+        }
+    }
+}
+
+/// Signs `Keccak256(payload)` and returns `(r, s, recovery id)`.
+fn sign_digest(signing_key: &SigningKey, payload: &[u8]) -> Result<([u8; 32], [u8; 32], u8), WalletError> {
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(&Keccak256::digest(payload))
+        .map_err(|err| WalletError::InvalidSignature(err.to_string()))?;
+    let (r, s) = signature.split_bytes();
+    Ok((r.into(), s.into(), recovery_id.to_byte()))
+}
+
+fn parse_address(address: &str) -> Result<Vec<u8>, WalletError> {
+    let checked = validate_hex_address(address)?;
+    Ok(hex::decode(&checked.address()[2..]).expect("validated addresses are hex"))
+}
+
+fn parse_hex(text: &str, name: &str) -> Result<Vec<u8>, WalletError> {
+    let text = text.trim();
+    hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .map_err(|_| WalletError::InvalidTransaction(format!("{name} must be hex")))
+}
+
+fn parse_fixed_hex(text: &str, size: usize, name: &str) -> Result<Vec<u8>, WalletError> {
+    let bytes = parse_hex(text, name)?;
+    if bytes.len() != size {
+        return Err(WalletError::InvalidTransaction(format!(
+            "{name} is {} bytes, expected {size}",
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn parse_hex_quantity(digits: &str) -> Result<Vec<u8>, WalletError> {
+    if digits.is_empty() {
+        return Err(WalletError::InvalidTransaction("empty hex quantity".to_string()));
+    }
+    let padded = if digits.len() % 2 == 1 { format!("0{digits}") } else { digits.to_string() };
+    let bytes = hex::decode(padded)
+        .map_err(|_| WalletError::InvalidTransaction(format!("quantity 0x{digits} is not hex")))?;
+    if strip_leading_zeros(&bytes).len() > MAX_QUANTITY_SIZE {
+        return Err(WalletError::InvalidTransaction(format!("quantity 0x{digits} exceeds 256 bits")));
+    }
+    Ok(bytes)
+}
+
+fn parse_decimal_quantity(digits: &str) -> Result<Vec<u8>, WalletError> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(WalletError::InvalidTransaction(format!(
+            "quantity {digits:?} must be a decimal or 0x hex integer"
+        )));
+    }
+
+    let mut value = [0u8; MAX_QUANTITY_SIZE];
+    for digit in digits.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for byte in value.iter_mut().rev() {
+            let product = u32::from(*byte) * 10 + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return Err(WalletError::InvalidTransaction(format!("quantity {digits} exceeds 256 bits")));
+        }
+    }
+    Ok(value.to_vec())
+}
+
+fn compare_quantities(a: &Quantity, b: &Quantity) -> std::cmp::Ordering {
+    a.0.len().cmp(&b.0.len()).then_with(|| a.0.cmp(&b.0))
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn encode_uint(big_endian: &[u8]) -> Vec<u8> {
+    encode_bytes(strip_leading_zeros(big_endian))
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if let [byte] = bytes {
+        if *byte < 0x80 {
+            return vec![*byte];
+        }
+    }
+    let mut output = encode_length(0x80, bytes.len());
+    output.extend_from_slice(bytes);
+    output
+}
+
+fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut output = encode_length(0xc0, items.iter().map(Vec::len).sum());
+    for item in items {
+        output.extend_from_slice(item);
+    }
+    output
+}
+
+fn encode_length(offset: u8, length: usize) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let length_bytes = length.to_be_bytes();
+    let length_bytes = strip_leading_zeros(&length_bytes);
+    let mut output = vec![offset + 55 + length_bytes.len() as u8];
+    output.extend_from_slice(length_bytes);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    // Example transaction from EIP-155, which uses a 20-byte recipient.
+    const EIP155_PRIVATE_KEY: [u8; 32] = [0x46; 32];
+    const EIP155_SIGNED: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400\
+                                 008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8\
+                                 997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    // TOS key of the `abandon … art` test mnemonic.
+    const TOS_PRIVATE_KEY: &str = "1053fae1b3ac64f178bcc21026fd06a3f4544ec2f35338b001f02d1d8efa3d5f";
+    const TOS_ADDRESS: &str = "0x9c57F3A17e19682cD9197A1AF278CF59f82Edcf871d630F28EcC8056F25c1CDb";
+
+    // Type 0x02 transfer to TOS_ADDRESS on mainnet, signed by an independent
+    // RLP and RFC 6979 implementation that reproduces EIP155_SIGNED.
+    const EIP1559_SIGNED: &str = "0x02f8ca82068207843b9aca008506fc23ac00825208a09c57f3a17e19682cd9197a1af278cf59f82edcf8\
+                                  71d630f28ecc8056f25c1cdb880de0b6b3a764000083c0ffeef845f843a09c57f3a17e19682cd9197a1a\
+                                  f278cf59f82edcf871d630f28ecc8056f25c1cdbe1a0000000000000000000000000000000000000000000\
+                                  000000000000000000000180a070b1e8d49b766f5eaa2234931485ae9d6bd566d373f1ac2709a5b7c2f122\
+                                  cf4fa05d611c6f90462f7fee52e960ced104f05d20d17d69f68d6d3556daf7666265c7";
+    const EIP1559_HASH: &str = "0x630bba96d62977afd5947e4378bea5e5f32e952b889fedace667aa530cd4c504";
+
+    fn tos_private_key() -> [u8; 32] {
+        hex::decode(TOS_PRIVATE_KEY).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn legacy_transactions_match_the_eip155_example() {
+        let fields = TransactionFields {
+            chain_id: 1,
+            nonce: 9,
+            gas: 21_000,
+            to: Some(vec![0x35; 20]),
+            value: Quantity::parse("1000000000000000000").unwrap(),
+            data: Vec::new(),
+            fee: Fee::Legacy {
+                gas_price: Quantity::parse("0x4a817c800").unwrap(),
+            },
+            access_list: Vec::new(),
+        };
+
+        let signed = fields.sign(&EIP155_PRIVATE_KEY).unwrap();
+        assert_eq!(signed.kind(), "legacy");
+        assert_eq!(signed.raw(), EIP155_SIGNED);
+        assert_eq!(
+            signed.hash(),
+            format!("0x{}", hex::encode(Keccak256::digest(hex::decode(&EIP155_SIGNED[2..]).unwrap())))
+        );
+    }
+
+    #[test]
+    fn eip1559_transactions_match_the_known_answer() {
+        let json = format!(
+            r#"{{
+                "chainId": 1666,
+                "nonce": 7,
+                "gas": 21000,
+                "to": "{TOS_ADDRESS}",
+                "value": "1000000000000000000",
+                "data": "0xc0ffee",
+                "maxFeePerGas": "30000000000",
+                "maxPriorityFeePerGas": "1000000000",
+                "accessList": [{{ "address": "{TOS_ADDRESS}", "storageKeys": ["0x{}01"] }}]
+            }}"#,
+            "00".repeat(31)
+        );
+        let signed = UnsignedTransaction::from_json(&json, Network::Mainnet).unwrap().sign(&tos_private_key()).unwrap();
+        assert_eq!(signed.kind(), "eip1559");
+        assert_eq!(signed.from(), TOS_ADDRESS);
+        assert_eq!(signed.raw(), EIP1559_SIGNED);
+        assert_eq!(signed.hash(), EIP1559_HASH);
+    }
+
+    #[test]
+    fn eip1559_transactions_recover_to_the_signer() {
+        let json = format!(
+            r#"{{
+                "type": "0x2",
+                "nonce": 0,
+                "gasLimit": "21000",
+                "to": "{TOS_ADDRESS}",
+                "value": "0xde0b6b3a7640000",
+                "maxFeePerGas": "30000000000",
+                "maxPriorityFeePerGas": "1000000000",
+                "accessList": [{{ "address": "{TOS_ADDRESS}", "storageKeys": ["0x{}"] }}]
+            }}"#,
+            "00".repeat(32)
+        );
+        let signed = UnsignedTransaction::from_json(&json, Network::Mainnet).unwrap().sign(&tos_private_key()).unwrap();
+        assert_eq!(signed.kind(), "eip1559");
+        assert_eq!(signed.from(), TOS_ADDRESS);
+
+        // The signature is the trailing `yParity, r, s` of the type 2 list.
+        let raw = hex::decode(&signed.raw()[2..]).unwrap();
+        assert_eq!(raw[0], EIP1559_TX_TYPE);
+        let (unsigned_body, signature) = raw.split_at(raw.len() - 67);
+        assert_eq!((signature[1], signature[34]), (0xa0, 0xa0));
+        let rs = Signature::from_scalars(
+            *k256::FieldBytes::from_slice(&signature[2..34]),
+            *k256::FieldBytes::from_slice(&signature[35..]),
+        )
+        .unwrap();
+
+        let fields = UnsignedTransaction::from_json(&json, Network::Mainnet).unwrap().fields().unwrap();
+        let mut unsigned = vec![EIP1559_TX_TYPE];
+        unsigned.extend(encode_list(&fields.payload_items()));
+        let recovered = VerifyingKey::recover_from_prehash(
+            &Keccak256::digest(&unsigned),
+            &rs,
+            RecoveryId::from_byte(signature[0]).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tos_address(recovered.to_encoded_point(false).as_bytes()), TOS_ADDRESS);
+        assert!(unsigned_body.ends_with(&fields.payload_items().concat()));
+    }
+
+    #[test]
+    fn invalid_transactions_are_rejected() {
+        let invalid = [
+            r#"{"chainId": 0, "nonce": 0, "gas": 21000, "gasPrice": 1}"#.to_string(),
+            r#"{"chainId": 1667, "nonce": 0, "gas": 21000, "gasPrice": 1}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000, "gasPrice": 1, "maxFeePerGas": 1}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000, "maxFeePerGas": 1, "maxPriorityFeePerGas": 2}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000, "gasPrice": 1, "accessList": []}"#.to_string(),
+            r#"{"type": 2, "nonce": 0, "gas": 21000, "gasPrice": 1}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000, "gasPrice": 1, "data": "0xzz"}"#.to_string(),
+            format!(r#"{{"nonce": 0, "gas": 21000, "gasPrice": 1, "to": "0x{}"}}"#, "35".repeat(20)),
+            format!(r#"{{"nonce": 0, "gas": 21000, "gasPrice": "0x1{}"}}"#, "00".repeat(32)),
+            r#"{"nonce": -1, "gas": 21000, "gasPrice": 1}"#.to_string(),
+            r#"{"nonce": 0, "gas": "0x10000000000000000", "gasPrice": 1}"#.to_string(),
+            r#"{"nonce": 0, "gas": 21000, "gasPrice": 1, "from": "me"}"#.to_string(),
+        ];
+
+        for json in invalid {
+            let result = UnsignedTransaction::from_json(&json, Network::Mainnet).and_then(|tx| tx.sign(&tos_private_key()));
+            assert!(
+                matches!(result, Err(WalletError::InvalidTransaction(_) | WalletError::InvalidAddress(_))),
+                "accepted {json}"
+            );
+        }
+
+        let mixed_case = TOS_ADDRESS.replace('F', "f");
+        let json = format!(r#"{{"nonce": 0, "gas": 21000, "gasPrice": 1, "to": "{mixed_case}"}}"#);
+        let result = UnsignedTransaction::from_json(&json, Network::Mainnet).unwrap().sign(&tos_private_key());
+        assert_eq!(result, Err(WalletError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn chain_id_defaults_to_the_network() {
+        assert_eq!(Network::Mainnet.chain_id(), 1666);
+        assert_eq!(Network::Testnet.chain_id(), 1667);
+
+        let json = r#"{"nonce": 0, "gas": 21000, "gasPrice": 1}"#;
+        for network in [Network::Mainnet, Network::Testnet] {
+            let fields = UnsignedTransaction::from_json(json, network).unwrap().fields().unwrap();
+            assert_eq!(fields.chain_id, network.chain_id());
+        }
+
+        let json = r#"{"chainId": "0x683", "nonce": 0, "gas": 21000, "gasPrice": 1}"#;
+        let transaction = UnsignedTransaction::from_json(json, Network::Testnet).unwrap();
+        assert_eq!(transaction.fields().unwrap().chain_id, 1667);
+    }
+
+    #[test]
+    fn nonce_and_gas_are_64_bit() {
+        let json = r#"{"nonce": "18446744073709551615", "gas": "0xffffffffffffffff", "gasPrice": 1}"#;
+        let fields = UnsignedTransaction::from_json(json, Network::Mainnet).unwrap().fields().unwrap();
+        assert_eq!((fields.nonce, fields.gas), (u64::MAX, u64::MAX));
+
+        let json = r#"{"nonce": "18446744073709551616", "gas": 21000, "gasPrice": 1}"#;
+        let result = UnsignedTransaction::from_json(json, Network::Mainnet).unwrap().fields();
+        assert!(matches!(
+            result,
+            Err(WalletError::InvalidTransaction(message)) if message == "nonce does not fit in 64 bits"
+        ));
+    }
+
+    #[test]
+    fn quantities_parse_numbers_decimal_and_hex() {
+        assert_eq!(Quantity::parse("0").unwrap(), Quantity::default());
+        assert_eq!(Quantity::parse("0x00").unwrap(), Quantity::default());
+        assert_eq!(Quantity::parse("20000000000").unwrap(), Quantity::parse("0x4a817c800").unwrap());
+        assert_eq!(Quantity::parse("0x4A817C800").unwrap(), Quantity::from(20_000_000_000));
+
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(Quantity::parse(max).unwrap(), Quantity(vec![0xff; 32]));
+        assert!(Quantity::parse("115792089237316195423570985008687907853269984665640564039457584007913129639936").is_err());
+        for bad in ["", "0x", "1.5", "-1", "0xg"] {
+            assert!(Quantity::parse(bad).is_err(), "accepted {bad:?}");
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Native Rust API test
+//!
+//! Exercises the public `rlib` API with fixed vectors so backend services can
+//! rely on it producing the same material as the paper wallet.
+
+use tos_paper_wallet::{
+    derive_tos_wallet, derive_uno_wallet, seed_from_phrase, DerivationPath, ExtendedPrivateKey,
+    LegacyWallet, Network, RangeIndex, WalletBundle, WalletError, WalletPair, WatchOnlyAddress,
+    DEFAULT_HD_PATH,
+};
+
+// SAFETY: TEST DATA ONLY - this is the publicly known BIP39 test mnemonic.
+const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+const TOS_ADDRESS: &str = "0x9c57F3A17e19682cD9197A1AF278CF59f82Edcf871d630F28EcC8056F25c1CDb";
+const TOS_PUBLIC_KEY: &str = "04dc286c821c7490afbe20a79d13123b9f41f3d7ef21e4a9caacd22f5983b28eca0e4dbd5624505a2c968fec15f25990c7324736890f6d0f74241f98e4259c1d42";
+const TOS_PRIVATE_KEY: &str = "1053fae1b3ac64f178bcc21026fd06a3f4544ec2f35338b001f02d1d8efa3d5f";
+const UNO_ADDRESS: &str = "0xeA1A1B540dD3794C33fC8EBD0ce1ed3b4Eb9FD5905fD129D6F030f98911178a2";
+const UNO_PUBLIC_KEY: &str = "48567021150b09cfa3895e0cc6bd21c836d65b7a8e2f08868ce89bae7b021d38";
+const UNO_PRIVATE_KEY: &str = "185721a1028bc0a87b5fb788bf9223e8ee6a8426b241ce2447920a77c9759207";
+const ACCOUNT_XPUB: &str = "xpub6DCVMGrdqkJkrRautCvc1Jqpv4hfbyPtSMpZ5KnYDeyggbX3sgFQTT3wjEqF1RtCMazBPhe7gaWYLgEn6qPLhCUddfndnu8Yq6Y3HygJmeu";
+const ACCOUNT_XPRV: &str = "xprv9zD8wmKk1NkTdwWSnBPbeAu6N2sBCWg358txGwNvfKShooBuL8w9uejTswUi7gymH3ZbHuW74kojPrLunNtD1DDz1GHWwWZSfMXTSsgP9Dp";
+
+#[test]
+fn test_bundle_from_mnemonic_matches_vectors() {
+    let bundle = WalletBundle::from_mnemonic(TEST_MNEMONIC, "", &DerivationPath::default()).unwrap();
+
+    assert_eq!(bundle.mnemonic(), TEST_MNEMONIC);
+    assert_eq!(bundle.hd_path(), DEFAULT_HD_PATH);
+    assert!(!bundle.passphrase_protected());
+
+    assert_eq!(bundle.tos().label(), "TOS");
+    assert_eq!(bundle.tos().signer_type(), "secp256k1");
+    assert_eq!(bundle.tos().address(), TOS_ADDRESS);
+    assert_eq!(bundle.tos().public_key(), TOS_PUBLIC_KEY);
+    assert_eq!(bundle.tos().private_key(), TOS_PRIVATE_KEY);
+
+    assert_eq!(bundle.uno().label(), "UNO");
+    assert_eq!(bundle.uno().signer_type(), "elgamal");
+    assert_eq!(bundle.uno().address(), UNO_ADDRESS);
+    assert_eq!(bundle.uno().public_key(), UNO_PUBLIC_KEY);
+    assert_eq!(bundle.uno().private_key(), UNO_PRIVATE_KEY);
+}
+
+#[test]
+fn test_per_signer_derivation_matches_bundle() {
+    let seed = seed_from_phrase(TEST_MNEMONIC, "").unwrap();
+    let path: DerivationPath = DEFAULT_HD_PATH.parse().unwrap();
+
+    assert_eq!(derive_tos_wallet(&seed, &path).unwrap().address(), TOS_ADDRESS);
+    assert_eq!(derive_uno_wallet(&seed, &path).unwrap().address(), UNO_ADDRESS);
+}
+
+#[test]
+fn test_range_first_entry_matches_default_bundle() {
+    let pairs = WalletPair::range_from_mnemonic(TEST_MNEMONIC, "", RangeIndex::Address, 0, 2, Network::Mainnet).unwrap();
+
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs[0].hd_path(), DEFAULT_HD_PATH);
+    assert_eq!(pairs[0].tos().address(), TOS_ADDRESS);
+    assert_eq!(pairs[0].uno().address(), UNO_ADDRESS);
+    assert_eq!(pairs[1].hd_path(), "m/44'/60'/0'/0/1");
+}
+
+#[test]
+fn test_errors_are_matchable() {
+    assert!(matches!(
+        seed_from_phrase("not a mnemonic", ""),
+        Err(WalletError::InvalidMnemonic(_))
+    ));
+    assert!(matches!(
+        "m/44'/x".parse::<DerivationPath>(),
+        Err(WalletError::InvalidPath(_))
+    ));
+}
+
+#[test]
+fn test_account_extended_keys_reach_default_wallet() {
+    let bundle = WalletBundle::from_mnemonic(TEST_MNEMONIC, "", &DerivationPath::default()).unwrap();
+
+    assert_eq!(bundle.account_path(), Some("m/44'/60'/0'"));
+    assert_eq!(bundle.account_xpub(), Some(ACCOUNT_XPUB));
+    assert_eq!(bundle.account_xprv(), None);
+    assert!(matches!(
+        WalletBundle::from_mnemonic(TEST_MNEMONIC, "", &DerivationPath::default()).unwrap().with_account_xprv("other"),
+        Err(WalletError::InvalidOptions(_))
+    ));
+    let bundle = bundle.with_account_xprv("").unwrap();
+    assert_eq!(bundle.account_xprv(), Some(ACCOUNT_XPRV));
+
+    let seed = seed_from_phrase(TEST_MNEMONIC, "").unwrap();
+    let account = ExtendedPrivateKey::derive(&seed, &"m/44'/60'/0'".parse().unwrap()).unwrap();
+    let leaf = account.derive_child(0).unwrap().derive_child(0).unwrap();
+    assert_eq!(hex::encode(leaf.private_key()), TOS_PRIVATE_KEY);
+    assert_eq!(leaf.extended_public_key().unwrap().fingerprint(), leaf.fingerprint().unwrap());
+
+    let shallow = WalletBundle::from_mnemonic(TEST_MNEMONIC, "", &"m/44'/60'".parse().unwrap()).unwrap();
+    assert_eq!(shallow.account_xpub(), None);
+}
+
+#[test]
+fn test_watch_only_addresses_match_private_derivation() {
+    let watch_only = WatchOnlyAddress::range_from_xpub(ACCOUNT_XPUB, 0, 3).unwrap();
+    let pairs = WalletPair::range_from_mnemonic(TEST_MNEMONIC, "", RangeIndex::Address, 0, 3, Network::Mainnet).unwrap();
+
+    assert_eq!(watch_only[0].address(), TOS_ADDRESS);
+    assert_eq!(watch_only[0].public_key(), TOS_PUBLIC_KEY);
+    for (watched, pair) in watch_only.iter().zip(&pairs) {
+        assert_eq!(watched.address(), pair.tos().address(), "index {}", watched.index());
+    }
+
+    assert!(matches!(
+        WatchOnlyAddress::range_from_xpub(ACCOUNT_XPRV, 0, 1),
+        Err(WalletError::InvalidExtendedKey(_))
+    ));
+}
+
+// SAFETY: TEST DATA ONLY - publicly known legacy seed from tests/regression_test.rs.
+const LEGACY_SEED: &str = "semifinal nugget hounded went gossip present jive school woozy double jittery tubes irritate unusual input blip academy leisure soil zero tufts upstairs hiker jaws unusual";
+const LEGACY_PRIVATE_KEY: &str = "f164f0cd577136547bd0b939050d596ec683d18341fb957a8f462be2c6b1330f";
+const LEGACY_ADDRESS: &str = "tos14gt7l6j52msqruq6thzc4m3agpmst8a20dynvhzzmsczv8edpvwqqxv22lu";
+
+#[test]
+fn test_legacy_wallet_restores_known_seed() {
+    let wallet = LegacyWallet::from_mnemonic(&LEGACY_SEED.to_uppercase(), Network::Mainnet).unwrap();
+
+    assert_eq!(wallet.mnemonic(), LEGACY_SEED);
+    assert_eq!(wallet.tos().signer_type(), "schnorr");
+    assert_eq!(wallet.tos().private_key(), LEGACY_PRIVATE_KEY);
+    assert_eq!(wallet.tos().address(), LEGACY_ADDRESS);
+    assert_eq!(wallet.tos().public_key(), "aa17efea5456e001f01a5dc58aee3d4077059faa7b49365c42dc30261f2d0b1c");
+
+    let without_checksum = LEGACY_SEED.rsplit_once(' ').unwrap().0;
+    assert_eq!(LegacyWallet::from_mnemonic(without_checksum, Network::Mainnet).unwrap().tos().address(), LEGACY_ADDRESS);
+}
+
+#[test]
+fn test_legacy_wallet_round_trips_and_rejects_bad_checksum() {
+    let generated = LegacyWallet::generate(Network::Mainnet).unwrap();
+    let restored = LegacyWallet::from_mnemonic(generated.mnemonic(), Network::Mainnet).unwrap();
+    assert_eq!(restored.tos().address(), generated.tos().address());
+    assert_eq!(generated.mnemonic().split(' ').count(), 25);
+
+    let mut words: Vec<&str> = LEGACY_SEED.split(' ').collect();
+    words[24] = if words[24] == "abbey" { "abducts" } else { "abbey" };
+    assert!(matches!(
+        LegacyWallet::from_mnemonic(&words.join(" "), Network::Mainnet),
+        Err(WalletError::InvalidMnemonic(_))
+    ));
+    assert!(matches!(
+        LegacyWallet::from_mnemonic(TEST_MNEMONIC, Network::Mainnet),
+        Err(WalletError::InvalidMnemonic(_))
+    ));
+}
+
+#[test]
+fn test_testnet_is_recorded_and_labelled() {
+    let path = DerivationPath::default();
+    let bundle = WalletBundle::from_mnemonic_in(None, TEST_MNEMONIC, "", &path, Network::Testnet).unwrap();
+
+    assert_eq!(bundle.network(), Network::Testnet);
+    assert_eq!(bundle.tos().label(), "TOS Testnet");
+    assert_eq!(bundle.uno().label(), "UNO Testnet");
+    assert_eq!(bundle.tos().address(), TOS_ADDRESS);
+    assert_eq!(WalletBundle::from_mnemonic(TEST_MNEMONIC, "", &path).unwrap().network(), Network::Mainnet);
+
+    let legacy = LegacyWallet::from_mnemonic(LEGACY_SEED, Network::Testnet).unwrap();
+    assert_eq!(legacy.network(), Network::Testnet);
+    assert_eq!(legacy.tos().address(), "tst14gt7l6j52msqruq6thzc4m3agpmst8a20dynvhzzmsczv8edpvwqqaurths");
+
+    assert_eq!("TestNet".parse::<Network>().unwrap(), Network::Testnet);
+    assert_eq!(
+        "devnet".parse::<Network>(),
+        Err(WalletError::UnsupportedNetwork("devnet".to_string()))
+    );
+}
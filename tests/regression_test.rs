@@ -1,3 +1,4 @@
+#![allow(clippy::empty_line_after_doc_comments)]
 /// Regression test to prevent address generation from breaking
 ///
 /// This test uses real user data to ensure that the address generation
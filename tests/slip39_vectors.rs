@@ -0,0 +1,69 @@
+//! SLIP-39 test
+//!
+//! Checks share combination against the official SLIP-39 test vectors
+//! (passphrase `TREZOR`), including the extendable backups that new shares
+//! use, and that shares emitted with a bundle restore it.
+
+use tos_paper_wallet::{
+    combine_slip39, DerivationPath, ExtendedPrivateKey, Language, Network, Slip39Group, WalletBundle, WalletError,
+};
+
+// The 45 vectors of the SLIP-39 reference implementation's vectors.json, as
+// [description, mnemonics, master secret hex, BIP32 master xprv of the secret];
+// the secret and xprv are "" when combining must fail.
+const VECTORS: &str = include_str!("slip39_vectors.json");
+
+#[test]
+fn test_official_vectors() {
+    let vectors: Vec<(String, Vec<String>, String, String)> = serde_json::from_str(VECTORS).unwrap();
+    assert_eq!(vectors.len(), 45);
+    assert_eq!(vectors.iter().filter(|(description, ..)| description.contains("xtendable")).count(), 4);
+
+    for (description, mnemonics, master_secret, xprv) in vectors {
+        let result = combine_slip39(&mnemonics, "TREZOR");
+        if master_secret.is_empty() {
+            assert!(
+                matches!(result, Err(WalletError::InvalidShare(_))),
+                "{description}: combined invalid shares"
+            );
+        } else {
+            let secret = result.unwrap();
+            assert_eq!(hex::encode(&secret), master_secret, "{description}");
+            assert_eq!(ExtendedPrivateKey::from_seed(&secret).unwrap().to_xprv(), xprv, "{description}");
+        }
+    }
+}
+
+#[test]
+fn test_shares_restore_the_bundle() {
+    let path = DerivationPath::default();
+    let groups = [Slip39Group::new(2, 3), Slip39Group::new(1, 1)];
+    let bundle = WalletBundle::generate_in(Language::Japanese, 12, "bip39 passphrase", &path, Network::Testnet)
+        .unwrap()
+        .with_slip39_shares(1, &groups, "slip39 passphrase")
+        .unwrap();
+    let shares = bundle.slip39_shares().unwrap();
+    assert_eq!(shares.len(), 2);
+    assert!(shares.iter().flatten().all(|share| share.split(' ').count() == 20));
+
+    for subset in [vec![&shares[0][2], &shares[0][0]], vec![&shares[1][0]]] {
+        let restored = WalletBundle::from_slip39_shares(
+            &subset,
+            "slip39 passphrase",
+            Language::Japanese,
+            "bip39 passphrase",
+            &path,
+            Network::Testnet,
+        )
+        .unwrap();
+        assert_eq!(restored.mnemonic(), bundle.mnemonic());
+        assert_eq!(restored.tos().address(), bundle.tos().address());
+        assert_eq!(restored.uno().address(), bundle.uno().address());
+        assert!(restored.slip39_shares().is_none());
+    }
+
+    assert!(matches!(
+        WalletBundle::from_slip39_shares(&[&shares[0][1]], "slip39 passphrase", Language::English, "", &path, Network::Mainnet),
+        Err(WalletError::InvalidShare(_))
+    ));
+}
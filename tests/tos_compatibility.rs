@@ -1,3 +1,4 @@
+#![allow(clippy::empty_line_after_doc_comments)]
 /// Integration test to verify 100% compatibility with TOS wallet
 ///
 /// This test generates 100 random private keys and verifies that:
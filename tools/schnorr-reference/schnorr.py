@@ -0,0 +1,148 @@
+"""Reference Schnorr signer for the TOS/GTOS scheme in src/schnorr.rs.
+
+A from-scratch ristretto255 (RFC 9496) implementation on the Python 3
+standard library only, so that the signatures pinned in src/schnorr.rs do not
+come from curve25519-dalek. Signatures use a random nonce.
+
+    python3 schnorr.py sign <uno|legacy> <private-key-hex> <message>
+    python3 schnorr.py verify <uno|legacy> <public-key-hex> <message> <signature-hex>
+"""
+
+import hashlib
+import os
+import sys
+
+p = 2**255 - 19
+l = 2**252 + 27742317777372353535851937790883648493
+d = (-121665 * pow(121666, p-2, p)) % p
+SQRT_M1 = pow(2, (p-1)//4, p)
+def isneg(x): return x % p & 1
+def cabs(x): x %= p; return p - x if isneg(x) else x
+def sqrt_ratio_m1(u, v):
+    u%=p; v%=p
+    v3 = v*v*v % p; v7 = v3*v3*v % p
+    r = u*v3*pow(u*v7 % p, (p-5)//8, p) % p
+    check = v*r*r % p
+    correct = check == u
+    flipped = check == (-u) % p
+    flipped_i = check == (-u*SQRT_M1) % p
+    r_prime = SQRT_M1*r % p
+    if flipped or flipped_i: r = r_prime
+    r = cabs(r)
+    return (correct or flipped), r
+INVSQRT_A_MINUS_D = sqrt_ratio_m1(1, (-1 - d) % p)[1]
+SQRT_AD_MINUS_ONE = 25063068953384623474111414158702152701244531502492656460079210482610430750235
+ONE_MINUS_D_SQ = (1 - d*d) % p
+D_MINUS_ONE_SQ = (d-1)*(d-1) % p
+def add(P, Q):
+    X1,Y1,Z1,T1 = P; X2,Y2,Z2,T2 = Q
+    A = (Y1-X1)*(Y2-X2) % p; B = (Y1+X1)*(Y2+X2) % p
+    C = T1*2*d*T2 % p; D = Z1*2*Z2 % p
+    E,F,G,H = B-A, D-C, D+C, B+A
+    return (E*F%p, G*H%p, F*G%p, E*H%p)
+def neg(P): X,Y,Z,T = P; return ((-X)%p, Y, Z, (-T)%p)
+def mul(P, k):
+    R = (0,1,1,0); k %= l
+    while k:
+        if k & 1: R = add(R, P)
+        P = add(P, P); k >>= 1
+    return R
+def encode(P):
+    X0,Y0,Z0,T0 = P
+    u1 = (Z0+Y0)*(Z0-Y0) % p; u2 = X0*Y0 % p
+    _, inv = sqrt_ratio_m1(1, u1*u2*u2 % p)
+    den1 = inv*u1 % p; den2 = inv*u2 % p
+    z_inv = den1*den2*T0 % p
+    ix0 = X0*SQRT_M1 % p; iy0 = Y0*SQRT_M1 % p
+    enchanted = den1*INVSQRT_A_MINUS_D % p
+    rotate = isneg(T0*z_inv)
+    if rotate: x, y, den_inv = iy0, ix0, enchanted
+    else: x, y, den_inv = X0, Y0, den2
+    if isneg(x*z_inv): y = (-y) % p
+    s = cabs(den_inv*(Z0-y))
+    return s.to_bytes(32, 'little')
+def decode(b):
+    s = int.from_bytes(b, 'little')
+    assert s < p and not isneg(s)
+    ss = s*s % p; u1 = (1 - ss) % p; u2 = (1 + ss) % p; u2s = u2*u2 % p
+    v = (-(d*u1*u1) - u2s) % p
+    ok, I = sqrt_ratio_m1(1, v*u2s % p)
+    dx = I*u2 % p; dy = I*dx*v % p
+    x = cabs(2*s*dx); y = u1*dy % p; t = x*y % p
+    assert ok and not isneg(t) and y != 0
+    return (x, y, 1, t)
+def map_elligator(t):
+    r = SQRT_M1*t*t % p
+    u = (r+1)*ONE_MINUS_D_SQ % p
+    v = (-1 - r*d) * (r+d) % p
+    was_square, s = sqrt_ratio_m1(u, v)
+    s_prime = (-cabs(s*t)) % p
+    if not was_square: s = s_prime; c = r
+    else: c = p - 1
+    N = (c*(r-1)*D_MINUS_ONE_SQ - v) % p
+    w0 = 2*s*v % p; w1 = N*SQRT_AD_MINUS_ONE % p; w2 = (1 - s*s) % p; w3 = (1 + s*s) % p
+    return (w0*w3 % p, w2*w1 % p, w1*w3 % p, w0*w2 % p)
+def from_uniform(b):
+    f = lambda h: int.from_bytes(h, 'little') & ((1<<255)-1)
+    return add(map_elligator(f(b[:32])), map_elligator(f(b[32:])))
+By = 4*pow(5, p-2, p) % p
+u = (By*By - 1) % p; v = (d*By*By + 1) % p
+Bx = pow(u*pow(v,p-2,p) % p, (p+3)//8, p)
+if (Bx*Bx - u*pow(v,p-2,p)) % p: Bx = Bx*SQRT_M1 % p
+if isneg(Bx): Bx = p - Bx
+BASE = (Bx, By, 1, Bx*By % p)
+
+
+def sha3_512(*parts):
+    return hashlib.sha3_512(b"".join(parts)).digest()
+
+
+def generator(scheme):
+    if scheme == "uno":
+        return from_uniform(sha3_512(encode(BASE)))
+    if scheme == "legacy":
+        return from_uniform(sha3_512(b"TOS_SIGNATURE_GENERATOR_H"))
+    raise SystemExit("scheme must be uno or legacy")
+
+
+def challenge(public_key, message, r):
+    return int.from_bytes(sha3_512(public_key, message, encode(r)), "little") % l
+
+
+def sign(scheme, private_key, message):
+    secret = int.from_bytes(private_key, "little")
+    if not 0 < secret < l:
+        raise SystemExit("private key is not a canonical non-zero scalar")
+    x = pow(secret, l - 2, l) if scheme == "uno" else secret
+    h = generator(scheme)
+    public_key = encode(mul(h, x))
+    k = int.from_bytes(os.urandom(64), "little") % l
+    e = challenge(public_key, message, mul(h, k))
+    s = (x * e + k) % l
+    return public_key, s.to_bytes(32, "little") + e.to_bytes(32, "little")
+
+
+def verify(scheme, public_key, message, signature):
+    if len(signature) != 64:
+        return False
+    s = int.from_bytes(signature[:32], "little")
+    e = int.from_bytes(signature[32:], "little")
+    if s >= l or e >= l:
+        return False
+    r = add(mul(generator(scheme), s), neg(mul(decode(public_key), e)))
+    return challenge(public_key, message, r) == e
+
+
+def main(args):
+    if len(args) == 4 and args[0] == "sign":
+        public_key, signature = sign(args[1], bytes.fromhex(args[2]), args[3].encode())
+        print("public key:", public_key.hex())
+        print("signature: ", signature.hex())
+    elif len(args) == 5 and args[0] == "verify":
+        print(verify(args[1], bytes.fromhex(args[2]), args[3].encode(), bytes.fromhex(args[4])))
+    else:
+        raise SystemExit(__doc__)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1:])
@@ -32,33 +32,42 @@ type walletEntry struct {
 }
 
 type walletBundle struct {
-	Mnemonic string      `json:"mnemonic"`
-	HDPath   string      `json:"hdPath"`
-	TOS      walletEntry `json:"tos"`
-	UNO      walletEntry `json:"uno"`
+	Mnemonic            string      `json:"mnemonic"`
+	HDPath              string      `json:"hdPath"`
+	PassphraseProtected bool        `json:"passphraseProtected"`
+	TOS                 walletEntry `json:"tos"`
+	UNO                 walletEntry `json:"uno"`
 }
 
 func main() {
-	if len(os.Args) != 2 {
-		fmt.Fprintln(os.Stderr, "usage: verify '<mnemonic>'")
+	if len(os.Args) < 2 || len(os.Args) > 4 {
+		fmt.Fprintln(os.Stderr, "usage: verify '<mnemonic>' [hd-path] [passphrase]")
 		os.Exit(1)
 	}
 
 	mnemonic := os.Args[1]
-	seed, err := bip39.NewSeedWithErrorChecking(mnemonic, "")
+	hdPath := defaultHDPath
+	if len(os.Args) >= 3 {
+		hdPath = os.Args[2]
+	}
+	passphrase := ""
+	if len(os.Args) == 4 {
+		passphrase = os.Args[3]
+	}
+	seed, err := bip39.NewSeedWithErrorChecking(mnemonic, passphrase)
 	if err != nil {
 		fmt.Fprintln(os.Stderr, err)
 		os.Exit(1)
 	}
 
-	tosPriv, err := deriveECDSAFromSeed(seed, defaultHDPath)
+	tosPriv, err := deriveECDSAFromSeed(seed, hdPath)
 	if err != nil {
 		fmt.Fprintln(os.Stderr, err)
 		os.Exit(1)
 	}
 	tosPub := crypto.FromECDSAPub(&tosPriv.PublicKey)
 
-	unoPriv, err := deriveElgamalPrivateFromSeed(seed, defaultHDPath)
+	unoPriv, err := deriveElgamalPrivateFromSeed(seed, hdPath)
 	if err != nil {
 		fmt.Fprintln(os.Stderr, err)
 		os.Exit(1)
@@ -75,8 +84,9 @@ func main() {
 	}
 
 	out := walletBundle{
-		Mnemonic: mnemonic,
-		HDPath:   defaultHDPath,
+		Mnemonic:            mnemonic,
+		HDPath:              hdPath,
+		PassphraseProtected: passphrase != "",
 		TOS: walletEntry{
 			Label:      "TOS",
 			SignerType: accountsigner.SignerTypeSecp256k1,